@@ -0,0 +1,156 @@
+use std::{error::Error, fmt, ops::Range};
+
+/// A parse failure pointing at the offending token of a puzzle input line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    span: Range<usize>,
+    source_line: String,
+    expected: String,
+}
+
+impl ParseError {
+    /// Builds an error for `token`, which must be a subslice of `source_line`.
+    ///
+    /// An empty `token` at the end of `source_line` reads as "found end of line".
+    ///
+    /// # Panics
+    ///
+    /// Panics if `token` does not lie within `source_line`.
+    #[must_use]
+    pub fn new(source_line: &str, token: &str, expected: impl Into<String>) -> Self {
+        let start = (token.as_ptr() as usize)
+            .checked_sub(source_line.as_ptr() as usize)
+            .filter(|start| start + token.len() <= source_line.len())
+            .expect("token should be a subslice of the source line");
+
+        ParseError {
+            line: 1,
+            span: start..start + token.len(),
+            source_line: source_line.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Sets the 1-based line number the error is reported at.
+    #[must_use]
+    pub fn with_line(self, line: usize) -> Self {
+        ParseError { line, ..self }
+    }
+
+    #[must_use]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Byte span of the offending token within its line.
+    #[must_use]
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// 1-based column of the offending token, counted in characters.
+    #[must_use]
+    pub fn column(&self) -> usize {
+        self.source_line[..self.span.start].chars().count() + 1
+    }
+
+    #[must_use]
+    pub fn found(&self) -> &str {
+        &self.source_line[self.span.clone()]
+    }
+
+    #[must_use]
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found ",
+            self.line,
+            self.column(),
+            self.expected
+        )?;
+        if self.found().is_empty() {
+            writeln!(f, "end of line")?;
+        } else {
+            writeln!(f, "`{}`", self.found())?;
+        }
+
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let padding = " ".repeat(self.column() - 1);
+        let carets = "^".repeat(self.found().chars().count().max(1));
+
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{number} | {}", self.source_line)?;
+        write!(f, "{gutter} | {padding}{carets}")
+    }
+}
+
+impl Error for ParseError {}
+
+/// Every line that failed to parse in strict mode.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseErrors(pub Vec<ParseError>);
+
+// Errors returned from `main` are printed with `Debug`, so show the rendered snippets there too.
+impl fmt::Debug for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} line(s) failed to parse", self.0.len())?;
+        for error in &self.0 {
+            write!(f, "\n\n{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseErrors {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn _should_locate_token() {
+        let line = "Game abc: 3 blue";
+        let error = ParseError::new(line, &line[5..8], "a game id").with_line(12);
+
+        assert_eq!(12, error.line());
+        assert_eq!(5..8, error.span());
+        assert_eq!(6, error.column());
+        assert_eq!("abc", error.found());
+    }
+
+    #[test]
+    fn _should_render_caret_under_token() {
+        let line = "Game abc: 3 blue";
+        let error = ParseError::new(line, &line[5..8], "a game id").with_line(12);
+
+        assert_eq!(
+            "line 12, column 6: expected a game id, found `abc`\n   |\n12 | Game abc: 3 blue\n   |      ^^^",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn _should_report_end_of_line() {
+        let line = "Card 1: 41 48";
+        let error = ParseError::new(line, &line[line.len()..], "`|`");
+
+        assert_eq!(
+            "line 1, column 14: expected `|`, found end of line\n  |\n1 | Card 1: 41 48\n  |              ^",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::ops::{Index, IndexMut};
+
+/// Offsets to the four orthogonal neighbours: north, east, south, west.
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets to all eight neighbours, clockwise from north.
+const SURROUNDING: [(isize, isize); 8] = [
+    (0, -1),  // North
+    (1, -1),  // North-East
+    (1, 0),   // East
+    (1, 1),   // South-East
+    (0, 1),   // South
+    (-1, 1),  // South-West
+    (-1, 0),  // West
+    (-1, -1), // North-West
+];
+
+/// A rectangular grid of cells, stored row by row and addressed as `(x, y)` from the top left.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A `width` by `height` grid with every cell set to `value`.
+    #[must_use]
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// A grid of `rows`, as wide as the longest row. Shorter rows are padded with
+    /// `T::default()`.
+    #[must_use]
+    pub fn from_rows<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>) -> Self
+    where
+        T: Default,
+    {
+        let mut cells = Vec::new();
+        let mut lengths = Vec::new();
+        for row in rows {
+            let start = cells.len();
+            cells.extend(row);
+            lengths.push(cells.len() - start);
+        }
+        let width = lengths.iter().copied().max().unwrap_or(0);
+        let height = lengths.len();
+
+        // Only ragged rows need copying into place.
+        if cells.len() != width * height {
+            let mut rows = cells.into_iter();
+            cells = Vec::with_capacity(width * height);
+            for length in lengths {
+                cells.extend(rows.by_ref().take(length));
+                cells.extend((length..width).map(|_| T::default()));
+            }
+        }
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// The cell at `(x, y)`, or `None` if it lies outside the grid.
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.contains(x, y).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.contains(x, y)
+            .then(|| &mut self.cells[y * self.width + x])
+    }
+
+    fn offsets(
+        &self,
+        x: usize,
+        y: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let neighbour = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+            self.contains(neighbour.0, neighbour.1).then_some(neighbour)
+        })
+    }
+
+    /// The coordinates of the cells north, east, south and west of `(x, y)`, where they
+    /// lie inside the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &ORTHOGONAL)
+    }
+
+    /// The coordinates of the eight cells around `(x, y)`, diagonals included, where they
+    /// lie inside the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &SURROUNDING)
+    }
+
+    /// The row at `y`, or `None` if it lies outside the grid.
+    #[must_use]
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// The column at `x` from top to bottom, empty if it lies outside the grid.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let skip = if x < self.width { x } else { self.cells.len() };
+        self.cells.iter().skip(skip).step_by(self.width.max(1))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).filter_map(|y| self.row(y))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Every cell with its coordinates, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| ((index % self.width, index / self.width), cell))
+    }
+
+    /// A grid built by picking, for each `(x, y)` of a `width` by `height` grid, the cell
+    /// of `self` at `source(x, y)`.
+    fn remap(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (x, y) = source(x, y);
+                self[(x, y)].clone()
+            })
+            .collect();
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// The grid mirrored along its main diagonal, so rows become columns.
+    #[must_use]
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+
+    /// The grid turned a quarter turn clockwise, so the first column becomes the top row.
+    #[must_use]
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.remap(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    /// The grid turned a quarter turn anticlockwise, so the last column becomes the top row.
+    #[must_use]
+    pub fn rotate_anticlockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.remap(self.height, self.width, |x, y| (width - 1 - y, x))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` lies outside the grid.
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!(
+                "({x}, {y}) is outside the {}x{} grid",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` lies outside the grid.
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) is outside the {width}x{height} grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::from_rows(["abc".chars(), "def".chars()])
+    }
+
+    #[test]
+    fn _should_read_rows_and_columns() {
+        let grid = grid();
+
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&'f'), grid.get(2, 1));
+        assert_eq!(None, grid.get(3, 0));
+        assert_eq!(Some(&['d', 'e', 'f'][..]), grid.row(1));
+        let columns: Vec<String> = grid.columns().map(Iterator::collect).collect();
+        assert_eq!(vec!["ad", "be", "cf"], columns);
+        assert_eq!(0, grid.column(3).count());
+
+        let padded = Grid::from_rows([vec![1, 2], vec![], vec![3, 4, 5], vec![6]]);
+        assert_eq!(
+            vec![&[1, 2, 0][..], &[0, 0, 0], &[3, 4, 5], &[6, 0, 0]],
+            padded.rows().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn _should_keep_neighbours_inside_grid() {
+        let grid = grid();
+
+        assert_eq!(
+            vec![(1, 0), (0, 1)],
+            grid.neighbours4(0, 0).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(1, 0), (2, 0), (2, 1), (0, 1), (0, 0)],
+            grid.neighbours8(1, 1).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn _should_transpose_and_rotate() {
+        let grid = grid();
+        let rows = |grid: &Grid<char>| -> Vec<String> {
+            grid.rows().map(|row| row.iter().collect()).collect()
+        };
+
+        assert_eq!(vec!["ad", "be", "cf"], rows(&grid.transpose()));
+        assert_eq!(vec!["da", "eb", "fc"], rows(&grid.rotate_clockwise()));
+        assert_eq!(vec!["cf", "be", "ad"], rows(&grid.rotate_anticlockwise()));
+        assert_eq!(
+            grid,
+            grid.rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise()
+        );
+    }
+}
@@ -0,0 +1,16 @@
+#![warn(clippy::pedantic)]
+
+mod error;
+mod grid;
+mod lines;
+mod parallel;
+mod solution;
+
+pub use error::{ParseError, ParseErrors};
+pub use grid::Grid;
+pub use lines::{
+    par_parse_lines, par_parse_lines_with, parse_lines, parse_lines_with, read_lines,
+    skipped_summary, stream_lines, stream_lines_with, Mode, Parsed,
+};
+pub use parallel::par_map;
+pub use solution::{solve, Part, Report, Solution};
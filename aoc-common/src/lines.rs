@@ -0,0 +1,293 @@
+use std::{
+    env,
+    error::Error,
+    io::{self, BufRead},
+    str::FromStr,
+};
+
+use crate::{par_map, ParseError, ParseErrors};
+
+/// How to treat lines that fail to parse.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Fail with every line error.
+    #[default]
+    Strict,
+    /// Skip bad lines, keeping track of what was skipped.
+    Lenient,
+}
+
+impl Mode {
+    /// Picks [`Mode::Lenient`] when the program was started with `--lenient`.
+    #[must_use]
+    pub fn from_args() -> Self {
+        if env::args().skip(1).any(|arg| arg == "--lenient") {
+            Mode::Lenient
+        } else {
+            Mode::Strict
+        }
+    }
+}
+
+/// Items parsed from an input, along with the errors of any lines skipped in lenient mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Parsed<T> {
+    pub items: Vec<T>,
+    pub skipped: Vec<ParseError>,
+}
+
+impl<T> Parsed<T> {
+    /// One-line summary of the skipped lines, if there are any.
+    #[must_use]
+    pub fn skipped_summary(&self) -> Option<String> {
+        skipped_summary(&self.skipped)
+    }
+}
+
+/// One-line summary of lines skipped in lenient mode, if there are any.
+#[must_use]
+pub fn skipped_summary(skipped: &[ParseError]) -> Option<String> {
+    if skipped.is_empty() {
+        return None;
+    }
+    let lines: Vec<_> = skipped.iter().map(|e| e.line().to_string()).collect();
+    Some(format!(
+        "Skipped {} unparseable line(s): {}",
+        skipped.len(),
+        lines.join(", ")
+    ))
+}
+
+/// Parses every non-blank line of `input`, numbering errors by their line in `input`.
+///
+/// # Errors
+///
+/// In [`Mode::Strict`], returns the errors of every line that failed to parse.
+pub fn parse_lines<T>(input: &str, mode: Mode) -> Result<Parsed<T>, ParseErrors>
+where
+    T: FromStr<Err = ParseError>,
+{
+    parse_lines_with(input, mode, str::parse)
+}
+
+/// Like [`parse_lines`], but parsing each line with `parse` instead of [`FromStr`].
+///
+/// # Errors
+///
+/// In [`Mode::Strict`], returns the errors of every line that failed to parse.
+pub fn parse_lines_with<T>(
+    input: &str,
+    mode: Mode,
+    parse: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Parsed<T>, ParseErrors> {
+    let results = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| parse(line).map_err(|error| error.with_line(index + 1)));
+
+    partition(results, mode)
+}
+
+/// Like [`parse_lines`], but parses the lines with [`par_map`].
+///
+/// # Errors
+///
+/// In [`Mode::Strict`], returns the errors of every line that failed to parse.
+pub fn par_parse_lines<T>(input: &str, mode: Mode) -> Result<Parsed<T>, ParseErrors>
+where
+    T: FromStr<Err = ParseError> + Send,
+{
+    par_parse_lines_with(input, mode, str::parse)
+}
+
+/// Like [`parse_lines_with`], but parses the lines with [`par_map`].
+///
+/// # Errors
+///
+/// In [`Mode::Strict`], returns the errors of every line that failed to parse.
+pub fn par_parse_lines_with<T: Send>(
+    input: &str,
+    mode: Mode,
+    parse: impl Fn(&str) -> Result<T, ParseError> + Sync + Send,
+) -> Result<Parsed<T>, ParseErrors> {
+    let lines: Vec<_> = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect();
+    let results = par_map(&lines, |&(index, line)| {
+        parse(line).map_err(|error| error.with_line(index + 1))
+    });
+
+    partition(results, mode)
+}
+
+/// Splits the parsed lines into items and skipped errors, keeping the input order.
+fn partition<T>(
+    results: impl IntoIterator<Item = Result<T, ParseError>>,
+    mode: Mode,
+) -> Result<Parsed<T>, ParseErrors> {
+    let mut items = vec![];
+    let mut skipped = vec![];
+
+    for result in results {
+        match result {
+            Ok(item) => items.push(item),
+            Err(error) => skipped.push(error),
+        }
+    }
+
+    if mode == Mode::Strict && !skipped.is_empty() {
+        return Err(ParseErrors(skipped));
+    }
+
+    Ok(Parsed { items, skipped })
+}
+
+/// Like [`parse_lines`], but reads `reader` one line at a time and hands each item to `each`,
+/// so the input never has to fit in memory.
+///
+/// Returns the errors of the lines skipped in [`Mode::Lenient`].
+///
+/// # Errors
+///
+/// Returns the first read error, or in [`Mode::Strict`] the errors of every line that failed
+/// to parse once the whole input has been read.
+pub fn stream_lines<T, R>(
+    reader: R,
+    mode: Mode,
+    each: impl FnMut(T),
+) -> Result<Vec<ParseError>, Box<dyn Error>>
+where
+    T: FromStr<Err = ParseError>,
+    R: BufRead,
+{
+    stream_lines_with(reader, mode, str::parse, each)
+}
+
+/// Like [`stream_lines`], but parsing each line with `parse` instead of [`FromStr`].
+///
+/// # Errors
+///
+/// Returns the first read error, or in [`Mode::Strict`] the errors of every line that failed
+/// to parse once the whole input has been read.
+pub fn stream_lines_with<T, R>(
+    reader: R,
+    mode: Mode,
+    parse: impl Fn(&str) -> Result<T, ParseError>,
+    mut each: impl FnMut(T),
+) -> Result<Vec<ParseError>, Box<dyn Error>>
+where
+    R: BufRead,
+{
+    let mut skipped = vec![];
+
+    read_lines(reader, |number, content| {
+        if !content.trim().is_empty() {
+            match parse(content) {
+                Ok(item) => each(item),
+                Err(error) => skipped.push(error.with_line(number)),
+            }
+        }
+    })?;
+
+    if mode == Mode::Strict && !skipped.is_empty() {
+        return Err(ParseErrors(skipped).into());
+    }
+
+    Ok(skipped)
+}
+
+/// Hands every line of `reader` to `each` with its 1-based number, without its line ending,
+/// reusing one buffer for the whole input.
+///
+/// Unlike [`stream_lines`], blank lines are passed on too.
+///
+/// # Errors
+///
+/// Returns the first read error.
+pub fn read_lines<R: BufRead>(mut reader: R, mut each: impl FnMut(usize, &str)) -> io::Result<()> {
+    let mut line = String::new();
+    let mut number = 0;
+
+    while reader.read_line(&mut line)? > 0 {
+        number += 1;
+        each(number, line.trim_end_matches(['\n', '\r']));
+        line.clear();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Number(usize);
+
+    impl FromStr for Number {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            s.parse()
+                .map(Number)
+                .map_err(|_| ParseError::new(s, s, "a number"))
+        }
+    }
+
+    #[test]
+    fn _should_collect_every_line_error_in_strict_mode() {
+        let errors = parse_lines::<Number>("1\nx\n3\n\ny", Mode::Strict).unwrap_err();
+
+        let lines: Vec<_> = errors.0.iter().map(ParseError::line).collect();
+        assert_eq!(vec![2, 5], lines);
+    }
+
+    #[test]
+    fn _should_skip_bad_lines_in_lenient_mode() {
+        let parsed = parse_lines::<Number>("1\nx\n3\n\ny", Mode::Lenient).unwrap();
+
+        let numbers: Vec<_> = parsed.items.iter().map(|n| n.0).collect();
+        assert_eq!(vec![1, 3], numbers);
+        assert_eq!(
+            Some("Skipped 2 unparseable line(s): 2, 5".to_string()),
+            parsed.skipped_summary()
+        );
+    }
+
+    #[test]
+    fn _should_stream_lines_from_reader() {
+        let mut sum = 0;
+        let skipped = stream_lines("1\r\nx\n3\n".as_bytes(), Mode::Lenient, |n: Number| {
+            sum += n.0;
+        })
+        .unwrap();
+
+        assert_eq!(4, sum);
+        assert_eq!(
+            vec![2],
+            skipped.iter().map(ParseError::line).collect::<Vec<_>>()
+        );
+        assert!(stream_lines("1\nx".as_bytes(), Mode::Strict, |_: Number| ()).is_err());
+    }
+
+    #[test]
+    fn _should_read_blank_lines_with_their_numbers() {
+        let mut lines = vec![];
+        read_lines("a\r\n\nb".as_bytes(), |number, line| {
+            lines.push((number, line.to_string()));
+        })
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                (1, "a".to_string()),
+                (2, String::new()),
+                (3, "b".to_string())
+            ],
+            lines
+        );
+    }
+}
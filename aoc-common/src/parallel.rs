@@ -0,0 +1,33 @@
+/// Maps `f` over `items` on rayon's thread pool when the `parallel` feature is enabled, and
+/// in order otherwise. The results keep the order of `items` either way.
+pub fn par_map<T, U>(items: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn _should_keep_items_in_order() {
+        let items: Vec<usize> = (0..1000).collect();
+
+        assert_eq!(
+            items.iter().map(|n| n * 2).collect::<Vec<_>>(),
+            par_map(&items, |n| n * 2)
+        );
+    }
+}
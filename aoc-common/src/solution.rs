@@ -0,0 +1,142 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::Mode;
+
+/// One of the two halves of a day's puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("expected part 1 or 2, found `{s}`")),
+        }
+    }
+}
+
+/// A day's puzzle: the parsed input, and the two answers computed from it.
+pub trait Solution: Sized {
+    type Answer: fmt::Display;
+
+    /// Parses the whole puzzle input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input does not follow the puzzle's format.
+    fn parse(input: &str, mode: Mode) -> Result<Self, Box<dyn Error>>;
+
+    fn part1(&self) -> Self::Answer;
+
+    fn part2(&self) -> Self::Answer;
+
+    /// Anything worth telling the user besides the answers, like lines skipped while parsing.
+    fn notes(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn answer(&self, part: Part) -> Self::Answer {
+        match part {
+            Part::One => self.part1(),
+            Part::Two => self.part2(),
+        }
+    }
+}
+
+/// The answers to the requested parts, along with the solution's notes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub answers: Vec<(Part, String)>,
+    pub notes: Vec<String>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (part, answer) in &self.answers {
+            writeln!(f, "Part {part}: {answer}")?;
+        }
+        for note in &self.notes {
+            writeln!(f, "{note}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses `input` as `S` and answers each of `parts`.
+///
+/// # Errors
+///
+/// Returns the parse error if `input` is not a valid puzzle input for `S`.
+pub fn solve<S: Solution>(
+    input: &str,
+    parts: &[Part],
+    mode: Mode,
+) -> Result<Report, Box<dyn Error>> {
+    let solution = S::parse(input, mode)?;
+
+    Ok(Report {
+        answers: parts
+            .iter()
+            .map(|&part| (part, solution.answer(part).to_string()))
+            .collect(),
+        notes: solution.notes(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum(Vec<usize>);
+
+    impl Solution for Sum {
+        type Answer = usize;
+
+        fn parse(input: &str, _mode: Mode) -> Result<Self, Box<dyn Error>> {
+            Ok(Sum(input
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()?))
+        }
+
+        fn part1(&self) -> usize {
+            self.0.iter().sum()
+        }
+
+        fn part2(&self) -> usize {
+            self.0.iter().product()
+        }
+    }
+
+    #[test]
+    fn _should_report_requested_parts() {
+        let report = solve::<Sum>("2 3 4", &[Part::Two], Mode::Strict).unwrap();
+
+        assert_eq!("Part 2: 24\n", report.to_string());
+    }
+
+    #[test]
+    fn _should_parse_part() {
+        assert_eq!(Ok(Part::One), "1".parse());
+        assert!("3".parse::<Part>().is_err());
+    }
+}
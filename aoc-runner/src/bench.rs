@@ -0,0 +1,259 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt, fs,
+    hint::black_box,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Mode, Solution};
+
+/// How long each phase of a day's solution took, at best, over several runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+}
+
+impl Timings {
+    #[must_use]
+    pub fn phases(&self) -> [(&'static str, Duration); 3] {
+        [
+            ("parse", self.parse),
+            ("part 1", self.part1),
+            ("part 2", self.part2),
+        ]
+    }
+}
+
+/// Times parsing `input` as `S` and answering both parts, keeping the fastest of `runs` runs.
+///
+/// # Errors
+///
+/// Returns the parse error if `input` is not a valid puzzle input for `S`.
+pub fn time<S: Solution>(input: &str, mode: Mode, runs: u32) -> Result<Timings, Box<dyn Error>> {
+    let mut timings = Timings {
+        parse: Duration::MAX,
+        part1: Duration::MAX,
+        part2: Duration::MAX,
+    };
+
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let solution = S::parse(black_box(input), mode)?;
+        timings.parse = timings.parse.min(start.elapsed());
+
+        let start = Instant::now();
+        black_box(solution.part1());
+        timings.part1 = timings.part1.min(start.elapsed());
+
+        let start = Instant::now();
+        black_box(solution.part2());
+        timings.part2 = timings.part2.min(start.elapsed());
+    }
+
+    Ok(timings)
+}
+
+/// Phase timings in nanoseconds, as stored in a baseline file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub parse: u64,
+    pub part1: u64,
+    pub part2: u64,
+}
+
+impl From<Timings> for BaselineEntry {
+    fn from(value: Timings) -> Self {
+        let nanos = |duration: Duration| u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        BaselineEntry {
+            parse: nanos(value.parse),
+            part1: nanos(value.part1),
+            part2: nanos(value.part2),
+        }
+    }
+}
+
+impl From<BaselineEntry> for Timings {
+    fn from(value: BaselineEntry) -> Self {
+        Timings {
+            parse: Duration::from_nanos(value.parse),
+            part1: Duration::from_nanos(value.part1),
+            part2: Duration::from_nanos(value.part2),
+        }
+    }
+}
+
+/// Saved timings keyed by `dayN`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline(pub BTreeMap<String, BaselineEntry>);
+
+impl Baseline {
+    /// Reads a baseline saved with [`Baseline::save`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid baseline.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|error| format!("unable to read {}: {error}", path.display()))?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Writes the baseline as TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, toml::to_string(self)?)
+            .map_err(|error| format!("unable to write {}: {error}", path.display()))?;
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn get(&self, day: u8) -> Option<Timings> {
+        self.0.get(&format!("day{day}")).copied().map(Timings::from)
+    }
+
+    pub fn insert(&mut self, day: u8, timings: Timings) {
+        self.0.insert(format!("day{day}"), timings.into());
+    }
+}
+
+/// One line of the timing table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+    pub day: u8,
+    pub phase: &'static str,
+    pub time: Duration,
+    pub baseline: Option<Duration>,
+}
+
+impl Row {
+    /// Relative change from the baseline, e.g. `0.5` for 50% slower.
+    #[must_use]
+    pub fn change(&self) -> Option<f64> {
+        self.baseline
+            .filter(|baseline| !baseline.is_zero())
+            .map(|baseline| self.time.as_secs_f64() / baseline.as_secs_f64() - 1.0)
+    }
+}
+
+/// Timings of every benchmarked day, compared against an optional baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchReport {
+    pub rows: Vec<Row>,
+    /// Slowdown above which a phase counts as a regression, e.g. `0.25` for 25%.
+    pub threshold: f64,
+}
+
+impl BenchReport {
+    #[must_use]
+    pub fn new(timings: &[(u8, Timings)], baseline: Option<&Baseline>, threshold: f64) -> Self {
+        let mut rows = vec![];
+
+        for &(day, timings) in timings {
+            let saved = baseline.and_then(|baseline| baseline.get(day));
+
+            for (index, (phase, time)) in timings.phases().into_iter().enumerate() {
+                rows.push(Row {
+                    day,
+                    phase,
+                    time,
+                    baseline: saved.map(|saved| saved.phases()[index].1),
+                });
+            }
+        }
+
+        BenchReport { rows, threshold }
+    }
+
+    #[must_use]
+    pub fn regressions(&self) -> Vec<&Row> {
+        self.rows
+            .iter()
+            .filter(|row| row.change().is_some_and(|change| change > self.threshold))
+            .collect()
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>3}  {:<6}  {:>12}  {:>12}  {:>8}",
+            "day", "phase", "time", "baseline", "change"
+        )?;
+        for row in &self.rows {
+            let baseline = row
+                .baseline
+                .map_or_else(|| "-".to_string(), |baseline| format!("{baseline:.2?}"));
+            let change = row.change().map_or_else(
+                || "-".to_string(),
+                |change| format!("{:+.1}%", change * 100.0),
+            );
+            let flag = if row.change().is_some_and(|change| change > self.threshold) {
+                "  REGRESSION"
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "{:>3}  {:<6}  {:>12}  {:>12}  {:>8}{flag}",
+                row.day,
+                row.phase,
+                format!("{:.2?}", row.time),
+                baseline,
+                change
+            )?;
+        }
+        write!(f, "{} regression(s)", self.regressions().len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timings(parse: u64, part1: u64, part2: u64) -> Timings {
+        BaselineEntry {
+            parse,
+            part1,
+            part2,
+        }
+        .into()
+    }
+
+    #[test]
+    fn _should_flag_regressions_over_threshold() {
+        let mut baseline = Baseline::default();
+        baseline.insert(5, timings(100, 100, 100));
+
+        let report = BenchReport::new(&[(5, timings(110, 200, 50))], Some(&baseline), 0.25);
+
+        let regressed: Vec<_> = report.regressions().iter().map(|row| row.phase).collect();
+        assert_eq!(vec!["part 1"], regressed);
+    }
+
+    #[test]
+    fn _should_list_days_missing_from_baseline() {
+        let report = BenchReport::new(&[(1, timings(1, 2, 3))], Some(&Baseline::default()), 0.25);
+
+        assert_eq!(3, report.rows.len());
+        assert!(report.rows.iter().all(|row| row.baseline.is_none()));
+    }
+
+    #[test]
+    fn _should_round_trip_baseline() {
+        let mut baseline = Baseline::default();
+        baseline.insert(3, timings(1_000, 2_000, 3_000));
+
+        let saved = toml::to_string(&baseline).unwrap();
+        assert_eq!(baseline, toml::from_str(&saved).unwrap());
+    }
+}
@@ -0,0 +1,651 @@
+#![warn(clippy::pedantic)]
+
+mod bench;
+mod verify;
+
+use std::{
+    error::Error,
+    fmt::{self, Write as _},
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read},
+    path::PathBuf,
+    str::FromStr,
+};
+
+pub use aoc_common::{solve, Mode, Part, Report, Solution};
+pub use bench::{time, Baseline, BaselineEntry, BenchReport, Row, Timings};
+pub use verify::{verify, Answers, Check, Expected, Outcome, Value, Verification};
+
+pub const USAGE: &str = "Usage: aoc [--day <1-5>] [--part <1|2>] [--input <path|->] [--lenient]
+           [--bag <colour=count,...>] [--game <id>]
+       aoc verify [--day <1-5>] [--answers <path>]
+       aoc export --day 2 [--input <path|->] [--format <json|lines>] [--lenient]
+       aoc bench [--day <1-5>] [--input <path|->] [--runs <n>] [--baseline <path>]
+                 [--threshold <percent>] [--save <path>]
+
+Runs every day against its data/dayN file unless --day is given.
+An input of `-` reads the puzzle from stdin.
+--bag checks day 2 against another bag than red=12,green=13,blue=14.
+--game notes a day 2 game's smallest bag and the largest bag that rejects it.
+
+`verify` reruns the solutions against the answers recorded in answers.toml.
+`export` prints the parsed day 2 games, as JSON or normalised puzzle lines.
+`bench` times parsing and both parts, optionally comparing against a saved baseline.";
+
+/// Parses and answers a day's puzzle, see [`solve`].
+pub type Solver = fn(&str, &[Part], Mode) -> Result<Report, Box<dyn Error>>;
+
+/// Times a day's puzzle, see [`time`].
+pub type Timer = fn(&str, Mode, u32) -> Result<Timings, Box<dyn Error>>;
+
+/// Answers a day's puzzle while reading it one line at a time.
+pub type Streamer = fn(&mut dyn BufRead, &[Part], Mode) -> Result<Report, Box<dyn Error>>;
+
+/// A day's [`Solution`], erased so every day fits in [`DAYS`].
+#[derive(Clone, Copy, Debug)]
+pub struct Day {
+    pub number: u8,
+    pub solve: Solver,
+    pub time: Timer,
+    /// Used by [`run`] instead of `solve` when set, so the input never has to fit in memory.
+    /// Streaming is sequential, so with the `parallel` feature files go to `solve` instead,
+    /// and only stdin is streamed.
+    pub stream: Option<Streamer>,
+}
+
+impl Day {
+    const fn of<S: Solution>(number: u8) -> Self {
+        Day {
+            number,
+            solve: solve::<S>,
+            time: time::<S>,
+            stream: None,
+        }
+    }
+
+    const fn streamed(self, stream: Streamer) -> Self {
+        Day {
+            stream: Some(stream),
+            ..self
+        }
+    }
+}
+
+pub const DAYS: [Day; 5] = [
+    Day::of::<day1::Document>(1)
+        .streamed(|input, parts, _mode| Ok(day1::stream(input)?.report(parts))),
+    Day::of::<day2::Games>(2).streamed(|input, parts, mode| {
+        let totals = day2::stream(
+            input,
+            mode,
+            &day2::Cubes::puzzle_bag(),
+            &day2::Palette::standard(),
+        )?;
+        Ok(totals.report(parts))
+    }),
+    Day::of::<day3::Schematic>(3),
+    Day::of::<day4::Scratchcards>(4)
+        .streamed(|input, parts, mode| Ok(day4::stream(input, mode)?.report(parts))),
+    Day::of::<day5::Almanac>(5),
+];
+
+/// Looks up the day registered as `number`.
+#[must_use]
+pub fn day(number: u8) -> Option<Day> {
+    DAYS.iter().find(|day| day.number == number).copied()
+}
+
+/// Where to read a puzzle input from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Input {
+    Path(PathBuf),
+    Stdin,
+}
+
+impl Input {
+    /// The checked-in input for `day`, relative to the workspace root.
+    #[must_use]
+    pub fn default_for(day: u8) -> Self {
+        Input::Path(PathBuf::from(format!("data/day{day}")))
+    }
+
+    /// Opens the input for reading line by line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub fn open(&self) -> io::Result<Box<dyn BufRead>> {
+        match self {
+            Input::Path(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+            Input::Stdin => Ok(Box::new(BufReader::new(io::stdin()))),
+        }
+    }
+
+    /// Reads the whole input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file or stdin cannot be read.
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            Input::Path(path) => fs::read_to_string(path),
+            Input::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+impl From<&str> for Input {
+    fn from(value: &str) -> Self {
+        if value == "-" {
+            Input::Stdin
+        } else {
+            Input::Path(PathBuf::from(value))
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArgsError(pub String);
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ArgsError {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Command {
+    /// Print the answers.
+    #[default]
+    Run,
+    /// Check the answers against the recorded ones.
+    Verify,
+    /// Time each phase of the solutions.
+    Bench,
+    /// Print the parsed input.
+    Export,
+}
+
+/// How `export` writes the parsed input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Json,
+    /// The puzzle's own line format.
+    Lines,
+}
+
+impl FromStr for Format {
+    type Err = ArgsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "lines" => Ok(Format::Lines),
+            _ => Err(ArgsError(format!("expected json or lines, found `{s}`"))),
+        }
+    }
+}
+
+/// Command line options of the `aoc` binary.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    pub day: Option<u8>,
+    pub part: Option<Part>,
+    pub input: Option<Input>,
+    pub answers: Option<PathBuf>,
+    pub runs: Option<u32>,
+    pub baseline: Option<PathBuf>,
+    pub save: Option<PathBuf>,
+    pub threshold: Option<f64>,
+    pub bag: Option<day2::Cubes>,
+    pub game: Option<usize>,
+    pub format: Option<Format>,
+    pub mode: Mode,
+    pub help: bool,
+}
+
+impl Args {
+    /// Parses the arguments following the program name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on unknown flags, missing or invalid values, or an `--input` without a
+    /// `--day` to feed it to.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, ArgsError> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter().peekable();
+
+        match args.peek().map(String::as_str) {
+            Some("verify") => parsed.command = Command::Verify,
+            Some("bench") => parsed.command = Command::Bench,
+            Some("export") => parsed.command = Command::Export,
+            _ => {}
+        }
+        if parsed.command != Command::Run {
+            args.next();
+        }
+
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| {
+                args.next()
+                    .ok_or_else(|| ArgsError(format!("missing value for {flag}")))
+            };
+
+            match arg.as_str() {
+                "--day" => {
+                    let requested = value("--day")?;
+                    parsed.day = Some(
+                        requested
+                            .parse()
+                            .ok()
+                            .filter(|&number| day(number).is_some())
+                            .ok_or_else(|| {
+                                ArgsError(format!("no solution for day `{requested}`"))
+                            })?,
+                    );
+                }
+                "--part" => parsed.part = Some(value("--part")?.parse().map_err(ArgsError)?),
+                "--input" => parsed.input = Some(Input::from(value("--input")?.as_str())),
+                "--answers" => parsed.answers = Some(PathBuf::from(value("--answers")?)),
+                "--runs" => {
+                    let runs = value("--runs")?;
+                    parsed.runs = Some(
+                        runs.parse()
+                            .map_err(|_| ArgsError(format!("invalid number of runs `{runs}`")))?,
+                    );
+                }
+                "--baseline" => parsed.baseline = Some(PathBuf::from(value("--baseline")?)),
+                "--save" => parsed.save = Some(PathBuf::from(value("--save")?)),
+                "--threshold" => {
+                    let threshold = value("--threshold")?;
+                    parsed.threshold = Some(
+                        threshold
+                            .trim_end_matches('%')
+                            .parse()
+                            .map_err(|_| ArgsError(format!("invalid threshold `{threshold}`")))?,
+                    );
+                }
+                "--bag" => {
+                    let bag = value("--bag")?;
+                    parsed.bag = Some(bag.parse().map_err(|error: aoc_common::ParseError| {
+                        ArgsError(format!(
+                            "invalid bag `{bag}`: expected {}",
+                            error.expected()
+                        ))
+                    })?);
+                }
+                "--game" => {
+                    let game = value("--game")?;
+                    parsed.game = Some(
+                        game.parse()
+                            .map_err(|_| ArgsError(format!("invalid game id `{game}`")))?,
+                    );
+                }
+                "--format" => parsed.format = Some(value("--format")?.parse()?),
+                "--lenient" => parsed.mode = Mode::Lenient,
+                "-h" | "--help" => parsed.help = true,
+                _ => return Err(ArgsError(format!("unexpected argument `{arg}`"))),
+            }
+        }
+
+        if parsed.input.is_some() && parsed.day.is_none() {
+            return Err(ArgsError("--input needs a --day to run".to_string()));
+        }
+        if parsed.bag.is_some() && (parsed.day != Some(2) || parsed.command != Command::Run) {
+            return Err(ArgsError(
+                "--bag only applies to running --day 2".to_string(),
+            ));
+        }
+        if parsed.game.is_some() && (parsed.day != Some(2) || parsed.command != Command::Run) {
+            return Err(ArgsError(
+                "--game only applies to running --day 2".to_string(),
+            ));
+        }
+        if parsed.command == Command::Export && parsed.day != Some(2) {
+            return Err(ArgsError("export only supports --day 2".to_string()));
+        }
+        if parsed.format.is_some() && parsed.command != Command::Export {
+            return Err(ArgsError("--format only applies to export".to_string()));
+        }
+        if parsed.command == Command::Verify && parsed.input.is_some() {
+            return Err(ArgsError(
+                "verify uses the inputs listed in the answers file, not --input".to_string(),
+            ));
+        }
+
+        Ok(parsed)
+    }
+
+    /// The days to run, in order.
+    #[must_use]
+    pub fn days(&self) -> Vec<u8> {
+        match self.day {
+            Some(day) => vec![day],
+            None => DAYS.iter().map(|day| day.number).collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
+}
+
+/// Runs every selected day and returns the combined output.
+///
+/// # Errors
+///
+/// Returns the first input that cannot be read or parsed, prefixed with its day.
+pub fn run(args: &Args) -> Result<String, Box<dyn Error>> {
+    let mut output = String::new();
+
+    for number in args.days() {
+        let day = day(number).ok_or_else(|| ArgsError(format!("no solution for day {number}")))?;
+
+        let report = if args.bag.is_some() || args.game.is_some() {
+            let bag = args.bag.clone().unwrap_or_else(day2::Cubes::puzzle_bag);
+            let puzzle = read_input(args, number)?;
+            day2::solve_with_bag(&puzzle, &args.parts(), args.mode, &bag, args.game)
+        } else if let Some(stream) = day.stream.filter(|_| streams(&input(args, number))) {
+            stream(&mut open_input(args, number)?, &args.parts(), args.mode)
+        } else {
+            (day.solve)(&read_input(args, number)?, &args.parts(), args.mode)
+        }
+        .map_err(|error| format!("Day {number}: {error}"))?;
+
+        write!(output, "Day {number}\n{report}")?;
+    }
+
+    Ok(output)
+}
+
+/// The exported games, kept apart from the notes so they can be piped to other tools.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Export {
+    pub games: String,
+    /// Lines skipped in [`Mode::Lenient`], meant for stderr.
+    pub notes: Vec<String>,
+}
+
+/// Parses the day 2 input and writes every game back out in the requested format.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or parsed.
+pub fn run_export(args: &Args) -> Result<Export, Box<dyn Error>> {
+    let puzzle = read_input(args, 2)?;
+    let games = day2::Games::parse_with(&puzzle, args.mode, None)?;
+
+    let mut output = String::new();
+    match args.format.unwrap_or_default() {
+        Format::Json => writeln!(output, "{}", serde_json::to_string_pretty(games.games())?)?,
+        Format::Lines => {
+            for game in games.games() {
+                writeln!(output, "{game}")?;
+            }
+        }
+    }
+
+    Ok(Export {
+        games: output,
+        notes: games.notes(),
+    })
+}
+
+/// Whether [`run`] streams `input` for the days that can: always without the `parallel`
+/// feature, and only from stdin with it, so whole files still get parsed in parallel.
+fn streams(input: &Input) -> bool {
+    !cfg!(feature = "parallel") || *input == Input::Stdin
+}
+
+fn input(args: &Args, day: u8) -> Input {
+    args.input.clone().unwrap_or(Input::default_for(day))
+}
+
+fn open_input(args: &Args, day: u8) -> Result<Box<dyn BufRead>, String> {
+    let input = input(args, day);
+
+    input
+        .open()
+        .map_err(|error| format!("Day {day}: unable to open {input:?}: {error}"))
+}
+
+fn read_input(args: &Args, day: u8) -> Result<String, String> {
+    let input = input(args, day);
+
+    input
+        .read()
+        .map_err(|error| format!("Day {day}: unable to read {input:?}: {error}"))
+}
+
+/// Times every selected day, saving the timings or comparing them to a baseline if asked.
+///
+/// # Errors
+///
+/// Returns an error if an input cannot be read or parsed, or a baseline cannot be read or
+/// written.
+pub fn run_bench(args: &Args) -> Result<BenchReport, Box<dyn Error>> {
+    let mut timings = vec![];
+
+    for number in args.days() {
+        let day = day(number).ok_or_else(|| ArgsError(format!("no solution for day {number}")))?;
+        let puzzle = read_input(args, number)?;
+
+        let timing = (day.time)(&puzzle, args.mode, args.runs.unwrap_or(10))
+            .map_err(|error| format!("Day {number}: {error}"))?;
+        timings.push((number, timing));
+    }
+
+    let baseline = args.baseline.as_deref().map(Baseline::load).transpose()?;
+
+    if let Some(path) = &args.save {
+        let mut saved = Baseline::default();
+        for &(day, timing) in &timings {
+            saved.insert(day, timing);
+        }
+        saved.save(path)?;
+    }
+
+    Ok(BenchReport::new(
+        &timings,
+        baseline.as_ref(),
+        args.threshold.unwrap_or(25.0) / 100.0,
+    ))
+}
+
+/// Loads the answers file and checks every selected day against it.
+///
+/// # Errors
+///
+/// Returns an error if the answers file cannot be read or parsed.
+pub fn run_verify(args: &Args) -> Result<Verification, Box<dyn Error>> {
+    let path = args
+        .answers
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("answers.toml"));
+    let answers = Answers::load(&path)?;
+
+    Ok(verify(&answers, &args.days()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args, ArgsError> {
+        Args::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn _should_parse_args() {
+        assert_eq!(
+            Ok(Args {
+                day: Some(5),
+                part: Some(Part::Two),
+                input: Some(Input::Stdin),
+                ..Args::default()
+            }),
+            args(&["--day", "5", "--part", "2", "--input", "-"])
+        );
+    }
+
+    #[test]
+    fn _should_parse_verify_command() {
+        assert_eq!(
+            Ok(Args {
+                command: Command::Verify,
+                answers: Some(PathBuf::from("other.toml")),
+                ..Args::default()
+            }),
+            args(&["verify", "--answers", "other.toml"])
+        );
+    }
+
+    #[test]
+    fn _should_parse_bench_command() {
+        assert_eq!(
+            Ok(Args {
+                command: Command::Bench,
+                day: Some(1),
+                input: Some(Input::from("other")),
+                runs: Some(50),
+                baseline: Some(PathBuf::from("bench.toml")),
+                threshold: Some(10.0),
+                ..Args::default()
+            }),
+            args(&[
+                "bench",
+                "--day",
+                "1",
+                "--input",
+                "other",
+                "--runs",
+                "50",
+                "--baseline",
+                "bench.toml",
+                "--threshold",
+                "10%"
+            ])
+        );
+    }
+
+    #[test]
+    fn _should_reject_bad_args() {
+        assert!(args(&["--day", "26"]).is_err());
+        assert!(args(&["--part"]).is_err());
+        assert!(args(&["--input", "data/day1"]).is_err());
+        assert!(args(&["--verbose"]).is_err());
+        assert!(args(&["verify", "--day", "1", "--input", "-"]).is_err());
+        assert!(args(&["bench", "--runs", "many"]).is_err());
+        assert!(args(&["--bag", "red=1"]).is_err());
+        assert!(args(&["--day", "2", "--bag", "red"]).is_err());
+        assert!(args(&["--game", "1"]).is_err());
+        assert!(args(&["--day", "2", "--game", "first"]).is_err());
+        assert!(args(&["export", "--day", "1"]).is_err());
+        assert!(args(&["--day", "2", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn _should_run_day_2_with_bag() {
+        let args = args(&[
+            "--day",
+            "2",
+            "--input",
+            "../data/day2",
+            "--bag",
+            "red=12,green=13,blue=14",
+        ])
+        .unwrap();
+
+        let output = run(&args).unwrap();
+        assert!(
+            output.starts_with("Day 2\nPart 1: 2439\nPart 2: 63711\n"),
+            "{output}"
+        );
+        assert!(output.contains("Minimal bag: blue="), "{output}");
+    }
+
+    #[test]
+    fn _should_note_game_bags() {
+        let args = args(&["--day", "2", "--input", "../data/day2", "--game", "1"]).unwrap();
+
+        let output = run(&args).unwrap();
+        assert!(output.contains("Game 1 minimal bag: "), "{output}");
+        assert!(
+            output.contains("Game 1 largest rejecting bag within blue=14"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn _should_export_games_as_json() {
+        let args = args(&["export", "--day", "2", "--input", "../data/day2"]).unwrap();
+
+        let export = run_export(&args).unwrap();
+        assert!(export.notes.is_empty());
+        let games: Vec<day2::Game> = serde_json::from_str(&export.games).unwrap();
+        assert_eq!(100, games.len());
+        assert_eq!(
+            "Game 1: 1 blue, 1 red; 10 red; 8 red, 1 blue, 1 green; 1 green, 5 blue",
+            games[0].to_string()
+        );
+        assert_eq!(10, games[0].cubes.get("red"));
+    }
+
+    #[test]
+    fn _should_note_lines_skipped_by_lenient_export() {
+        let path = std::env::temp_dir().join("aoc-runner-lenient-export.txt");
+        fs::write(&path, "Game 1: 3 blue\nnot a game\nGame 2: 1 red\n").unwrap();
+        let args = Args {
+            command: Command::Export,
+            day: Some(2),
+            input: Some(Input::Path(path.clone())),
+            format: Some(Format::Lines),
+            mode: Mode::Lenient,
+            ..Args::default()
+        };
+
+        let export = run_export(&args);
+        fs::remove_file(path).unwrap();
+        let export = export.unwrap();
+        assert_eq!("Game 1: 3 blue\nGame 2: 1 red\n", export.games);
+        assert_eq!(
+            vec!["Skipped 1 unparseable line(s): 2".to_string()],
+            export.notes
+        );
+    }
+
+    #[test]
+    fn _should_run_day_against_file() {
+        let args = Args {
+            day: Some(5),
+            input: Some(Input::from("../data/ex5")),
+            ..Args::default()
+        };
+
+        assert_eq!("Day 5\nPart 1: 35\nPart 2: 46\n", run(&args).unwrap());
+    }
+
+    #[test]
+    fn _should_stream_day_from_file() {
+        let args = Args {
+            day: Some(4),
+            part: Some(Part::Two),
+            input: Some(Input::from("../day4/data/ex4")),
+            ..Args::default()
+        };
+
+        assert_eq!("Day 4\nPart 2: 30\n", run(&args).unwrap());
+    }
+}
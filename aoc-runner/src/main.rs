@@ -0,0 +1,58 @@
+#![warn(clippy::pedantic)]
+
+use std::{env, process::ExitCode};
+
+use aoc_runner::{run, run_bench, run_export, run_verify, Args, Command, USAGE};
+
+fn main() -> ExitCode {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if args.help {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let result = match args.command {
+        Command::Run => run(&args).map(|output| (output, true)),
+        Command::Verify => run_verify(&args)
+            .map(|verification| (format!("{verification}\n"), verification.is_success())),
+        Command::Export => run_export(&args).map(|export| {
+            for note in &export.notes {
+                eprintln!("{note}");
+            }
+            (export.games, true)
+        }),
+        Command::Bench => run_bench(&args).map(|report| {
+            let warning = if cfg!(debug_assertions) {
+                "Note: built without optimisations, try `cargo run --release -- bench`\n"
+            } else {
+                ""
+            };
+            (
+                format!("{warning}{report}\n"),
+                report.regressions().is_empty(),
+            )
+        }),
+    };
+
+    match result {
+        Ok((output, success)) => {
+            print!("{output}");
+            if success {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
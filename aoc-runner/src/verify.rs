@@ -0,0 +1,286 @@
+use std::{
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{day, Input, Mode, Part};
+
+/// A recorded answer, written either as a number or as a string.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Number(u64),
+    Text(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(number) => write!(f, "{number}"),
+            Value::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+/// The known-correct answers of one day for one input file.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Expected {
+    pub day: u8,
+    pub input: PathBuf,
+    pub part1: Option<Value>,
+    pub part2: Option<Value>,
+}
+
+impl Expected {
+    #[must_use]
+    pub fn part(&self, part: Part) -> Option<&Value> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+}
+
+/// The contents of an `answers.toml` file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct Answers {
+    #[serde(default)]
+    pub answer: Vec<Expected>,
+    /// Directory the input paths are relative to.
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+impl Answers {
+    /// Reads an answers file, resolving its input paths against the file's directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not valid TOML.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|error| format!("unable to read {}: {error}", path.display()))?;
+        let root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        Self::parse(&content, root)
+    }
+
+    /// Parses the contents of an answers file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `content` is not valid TOML or does not match the expected layout.
+    pub fn parse(content: &str, root: PathBuf) -> Result<Self, Box<dyn Error>> {
+        let answers: Answers = toml::from_str(content)?;
+
+        Ok(Answers { root, ..answers })
+    }
+
+    /// The recorded inputs for `day`, followed by the default input if it has no record.
+    fn inputs(&self, day: u8) -> Vec<(PathBuf, Option<&Expected>)> {
+        let mut inputs: Vec<_> = self
+            .answer
+            .iter()
+            .filter(|expected| expected.day == day)
+            .map(|expected| (expected.input.clone(), Some(expected)))
+            .collect();
+
+        if let Input::Path(default) = Input::default_for(day) {
+            if !inputs.iter().any(|(input, _)| *input == default) {
+                inputs.push((default, None));
+            }
+        }
+
+        inputs
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// No answer is recorded; `actual` is what the solution currently returns.
+    Missing {
+        actual: String,
+    },
+    Error(String),
+}
+
+/// The result of checking one part of one day against one input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub part: Part,
+    pub input: PathBuf,
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.outcome {
+            Outcome::Pass => "PASS",
+            Outcome::Fail { .. } => "FAIL",
+            Outcome::Missing { .. } => "MISSING",
+            Outcome::Error(_) => "ERROR",
+        };
+        write!(
+            f,
+            "{label:<7} day {} part {}  {}",
+            self.day,
+            self.part,
+            self.input.display()
+        )?;
+
+        match &self.outcome {
+            Outcome::Pass => Ok(()),
+            Outcome::Fail { expected, actual } => {
+                write!(f, "\n        - {expected}\n        + {actual}")
+            }
+            Outcome::Missing { actual } => write!(f, "\n        + {actual}"),
+            Outcome::Error(error) => write!(f, "\n        {error}"),
+        }
+    }
+}
+
+/// Every check made by [`verify`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Verification(pub Vec<Check>);
+
+impl Verification {
+    fn count(&self, matches: fn(&Outcome) -> bool) -> usize {
+        self.0
+            .iter()
+            .filter(|check| matches(&check.outcome))
+            .count()
+    }
+
+    /// Whether no answer changed and no solution failed to run.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.count(|outcome| matches!(outcome, Outcome::Fail { .. } | Outcome::Error(_))) == 0
+    }
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.0 {
+            writeln!(f, "{check}")?;
+        }
+        write!(
+            f,
+            "{} passed, {} failed, {} missing, {} errors",
+            self.count(|outcome| matches!(outcome, Outcome::Pass)),
+            self.count(|outcome| matches!(outcome, Outcome::Fail { .. })),
+            self.count(|outcome| matches!(outcome, Outcome::Missing { .. })),
+            self.count(|outcome| matches!(outcome, Outcome::Error(_))),
+        )
+    }
+}
+
+/// Reruns each of `days` against every recorded input, and its default input.
+#[must_use]
+pub fn verify(answers: &Answers, days: &[u8]) -> Verification {
+    let mut checks = vec![];
+
+    for &number in days {
+        let Some(solve) = day(number).map(|day| day.solve) else {
+            continue;
+        };
+
+        for (input, expected) in answers.inputs(number) {
+            let report = fs::read_to_string(answers.root.join(&input))
+                .map_err(|error| format!("unable to read input: {error}"))
+                .and_then(|puzzle| {
+                    solve(&puzzle, &Part::ALL, Mode::Strict).map_err(|error| error.to_string())
+                });
+
+            for part in Part::ALL {
+                let outcome = match &report {
+                    Err(error) => Outcome::Error(error.clone()),
+                    Ok(report) => {
+                        let actual = report
+                            .answers
+                            .iter()
+                            .find(|(answered, _)| *answered == part)
+                            .map(|(_, answer)| answer.clone())
+                            .unwrap_or_default();
+
+                        match expected.and_then(|expected| expected.part(part)) {
+                            None => Outcome::Missing { actual },
+                            Some(expected) if expected.to_string() == actual => Outcome::Pass,
+                            Some(expected) => Outcome::Fail {
+                                expected: expected.to_string(),
+                                actual,
+                            },
+                        }
+                    }
+                };
+
+                checks.push(Check {
+                    day: number,
+                    part,
+                    input: input.clone(),
+                    outcome,
+                });
+            }
+        }
+    }
+
+    Verification(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = r#"
+[[answer]]
+day = 5
+input = "data/ex5"
+part1 = 35
+part2 = "47"
+"#;
+
+    #[test]
+    fn _should_report_pass_fail_and_missing() {
+        let answers = Answers::parse(ANSWERS, PathBuf::from("..")).unwrap();
+        let verification = verify(&answers, &[5]);
+
+        let outcomes: Vec<_> = verification
+            .0
+            .iter()
+            .filter(|check| check.input == Path::new("data/ex5"))
+            .map(|check| check.outcome.clone())
+            .collect();
+        assert_eq!(
+            vec![
+                Outcome::Pass,
+                Outcome::Fail {
+                    expected: "47".to_string(),
+                    actual: "46".to_string()
+                }
+            ],
+            outcomes
+        );
+        assert!(verification
+            .0
+            .iter()
+            .any(|check| check.input == Path::new("data/day5")
+                && matches!(check.outcome, Outcome::Missing { .. })));
+        assert!(!verification.is_success());
+    }
+
+    #[test]
+    fn _should_pass_recorded_answers() {
+        let answers = Answers::load(Path::new("../answers.toml")).unwrap();
+
+        let verification = verify(&answers, &[4, 5]);
+        assert!(verification.is_success(), "{verification}");
+    }
+}
@@ -0,0 +1,431 @@
+//! Day 1: Trebuchet?! Recovering calibration values from an amended document.
+
+mod vocabulary;
+
+use aoc_common::{par_map, read_lines, Part, Report};
+pub use aoc_common::{Mode, Solution};
+use std::{
+    error::Error,
+    fmt,
+    io::{self, BufRead},
+    sync::{LazyLock, OnceLock},
+};
+pub use vocabulary::{Digit, Vocabulary, ENGLISH, ROMAN};
+
+static PUZZLE_VOCABULARY: LazyLock<Vocabulary> = LazyLock::new(Vocabulary::english);
+
+/// Why a line has no calibration value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reason {
+    NoDigits,
+    /// The combined digits do not fit in a `usize`, only possible with large vocabulary values.
+    Overflow,
+}
+
+/// A line of the document that has no calibration value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CalibrationError {
+    pub line: usize,
+    pub content: String,
+    pub reason: Reason,
+}
+
+impl CalibrationError {
+    /// An error on line 1, see [`CalibrationError::with_line`].
+    pub fn new(content: &str, reason: Reason) -> Self {
+        CalibrationError {
+            line: 1,
+            content: content.to_string(),
+            reason,
+        }
+    }
+
+    /// Sets the 1-based line number the error is reported at.
+    pub fn with_line(self, line: usize) -> Self {
+        CalibrationError { line, ..self }
+    }
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.reason {
+            Reason::NoDigits => "no digits",
+            Reason::Overflow => "calibration value overflows",
+        };
+        write!(f, "line {}: {reason} in `{}`", self.line, self.content)
+    }
+}
+
+impl Error for CalibrationError {}
+
+/// Every numeric or spelled-out English digit of `line`, see [`Vocabulary::find_digits`].
+pub fn find_digits(line: &str) -> impl Iterator<Item = Digit> + '_ {
+    PUZZLE_VOCABULARY.find_digits(line)
+}
+
+/// Every numeric digit `0` to `9` of `line`, in order. Other scripts' digits, like `٣`, are
+/// not counted, matching [`find_digits`].
+pub fn get_digits(line: &str) -> Vec<usize> {
+    line.bytes()
+        .filter(u8::is_ascii_digit)
+        .map(|digit| usize::from(digit - b'0'))
+        .collect()
+}
+
+/// The first digit of `line`, either numeric or spelled out.
+pub fn get_first_digit_string(line: &str) -> Option<&str> {
+    let digit = find_digits(line).next()?;
+    Some(&line[digit.offset..digit.offset + digit.len])
+}
+
+/// The last digit of `line`, either numeric or spelled out.
+pub fn get_last_digit_string(line: &str) -> Option<&str> {
+    let digit = find_digits(line).last()?;
+    Some(&line[digit.offset..digit.offset + digit.len])
+}
+
+/// Combines the first and last numeric digits of `line` into a two-digit number.
+pub fn get_calibration_number(line: &str) -> Result<usize, CalibrationError> {
+    let digits = get_digits(line);
+    match (digits.first(), digits.last()) {
+        (Some(first), Some(last)) => Ok(first * 10 + last),
+        _ => Err(CalibrationError::new(line, Reason::NoDigits)),
+    }
+}
+
+/// Converts a numeric or spelled-out digit into its value, or 0 if it is neither.
+pub fn parse_number_string(string: &str) -> usize {
+    string
+        .parse::<usize>()
+        .ok()
+        .or_else(|| PUZZLE_VOCABULARY.value(string))
+        .unwrap_or(0)
+}
+
+/// Like [`get_calibration_number`], but also counting spelled-out digits.
+pub fn get_stringified_calibration_number(line: &str) -> Result<usize, CalibrationError> {
+    PUZZLE_VOCABULARY.calibration_number(line)
+}
+
+/// The sum of every line's calibration value, leaving out the lines that have none.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Calibration {
+    pub sum: usize,
+    pub failures: Vec<CalibrationError>,
+}
+
+impl Calibration {
+    /// Adds the value of `line`, the `number`th line, or records why it has none.
+    pub fn add(
+        &mut self,
+        number: usize,
+        line: &str,
+        value: fn(&str) -> Result<usize, CalibrationError>,
+    ) {
+        self.record(number, line, value(line));
+    }
+
+    /// Adds a value already found for `line`, the `number`th line.
+    pub fn record(&mut self, number: usize, line: &str, value: Result<usize, CalibrationError>) {
+        match value.and_then(|value| {
+            self.sum
+                .checked_add(value)
+                .ok_or_else(|| CalibrationError::new(line, Reason::Overflow))
+        }) {
+            Ok(sum) => self.sum = sum,
+            Err(error) => self.failures.push(error.with_line(number)),
+        }
+    }
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.sum)?;
+        if !self.failures.is_empty() {
+            write!(f, " (partial sum, {} line(s) failed)", self.failures.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// The calibration document, one amended calibration value per line.
+pub struct Document {
+    lines: Vec<String>,
+    /// The failures of each part, kept from the first time it is answered.
+    failures: [OnceLock<Vec<CalibrationError>>; 2],
+}
+
+impl Document {
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Sums `value` over every line, collecting the lines it fails on.
+    ///
+    /// Failures on lines that overflow the running sum are reported as [`Reason::Overflow`].
+    pub fn calibrate(&self, value: fn(&str) -> Result<usize, CalibrationError>) -> Calibration {
+        let mut calibration = Calibration::default();
+        for (index, line) in self.lines.iter().enumerate() {
+            calibration.add(index + 1, line, value);
+        }
+        calibration
+    }
+
+    /// Calibrates the document for `part`, keeping the failures for [`Solution::notes`].
+    fn answer_part(
+        &self,
+        part: Part,
+        value: fn(&str) -> Result<usize, CalibrationError>,
+    ) -> Calibration {
+        let calibration = self.par_calibrate(value);
+        self.failures[part as usize].get_or_init(|| calibration.failures.clone());
+        calibration
+    }
+
+    /// Like [`Document::calibrate`], but finding each line's value with [`par_map`].
+    pub fn par_calibrate(&self, value: fn(&str) -> Result<usize, CalibrationError>) -> Calibration {
+        let values = par_map(&self.lines, |line| value(line));
+
+        let mut calibration = Calibration::default();
+        for (index, (line, value)) in self.lines.iter().zip(values).enumerate() {
+            calibration.record(index + 1, line, value);
+        }
+        calibration
+    }
+}
+
+/// Both answers, computed while reading the document one line at a time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub part1: Calibration,
+    pub part2: Calibration,
+}
+
+impl Totals {
+    /// The answers and failures of `parts`.
+    pub fn report(&self, parts: &[Part]) -> Report {
+        let parts: Vec<_> = parts
+            .iter()
+            .map(|&part| match part {
+                Part::One => (part, &self.part1),
+                Part::Two => (part, &self.part2),
+            })
+            .collect();
+
+        Report {
+            answers: parts
+                .iter()
+                .map(|(part, calibration)| (*part, calibration.to_string()))
+                .collect(),
+            notes: parts
+                .iter()
+                .flat_map(|(part, calibration)| {
+                    calibration
+                        .failures
+                        .iter()
+                        .map(move |failure| format!("Part {part}: {failure}"))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Calibrates the lines read from `reader` without holding the document in memory.
+pub fn stream(reader: impl BufRead) -> io::Result<Totals> {
+    let mut totals = Totals::default();
+
+    read_lines(reader, |number, line| {
+        totals.part1.add(number, line, get_calibration_number);
+        totals
+            .part2
+            .add(number, line, get_stringified_calibration_number);
+    })?;
+
+    Ok(totals)
+}
+
+impl Solution for Document {
+    type Answer = Calibration;
+
+    fn parse(input: &str, _mode: Mode) -> Result<Self, Box<dyn Error>> {
+        Ok(Document {
+            lines: input.lines().map(String::from).collect(),
+            failures: Default::default(),
+        })
+    }
+
+    fn part1(&self) -> Calibration {
+        self.answer_part(Part::One, get_calibration_number)
+    }
+
+    fn part2(&self) -> Calibration {
+        self.answer_part(Part::Two, get_stringified_calibration_number)
+    }
+
+    /// The failures of the parts answered so far.
+    fn notes(&self) -> Vec<String> {
+        Part::ALL
+            .into_iter()
+            .zip(&self.failures)
+            .flat_map(|(part, failures)| {
+                failures
+                    .get()
+                    .into_iter()
+                    .flatten()
+                    .map(move |failure| format!("Part {part}: {failure}"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn _should_get_all_digits() {
+        let ex0 = "1abc2";
+        let mut digits = get_digits(ex0);
+        assert_eq!(vec![1, 2], digits);
+
+        let ex1 = "pqr3stu8vwx";
+        digits = get_digits(ex1);
+        assert_eq!(vec![3, 8], digits);
+
+        let ex2 = "a1b2c3d4e5f";
+        digits = get_digits(ex2);
+        assert_eq!(vec![1, 2, 3, 4, 5], digits);
+
+        let ex3 = "treb7uchet";
+        digits = get_digits(ex3);
+        assert_eq!(vec![7], digits);
+
+        let arabic_indic = "a٣b";
+        assert!(get_digits(arabic_indic).is_empty());
+        assert_eq!(
+            Err(Reason::NoDigits),
+            get_calibration_number(arabic_indic).map_err(|error| error.reason)
+        );
+        assert_eq!(None, find_digits(arabic_indic).next());
+    }
+
+    #[test]
+    fn _should_get_calibration_number() {
+        let ex0 = "1abc2";
+        let mut cal_num = get_calibration_number(ex0).unwrap();
+        assert_eq!(12, cal_num);
+
+        let ex1 = "pqr3stu8vwx";
+        cal_num = get_calibration_number(ex1).unwrap();
+        assert_eq!(38, cal_num);
+
+        let ex2 = "a1b2c3d4e5f";
+        cal_num = get_calibration_number(ex2).unwrap();
+        assert_eq!(15, cal_num);
+
+        let ex3 = "treb7uchet";
+        cal_num = get_calibration_number(ex3).unwrap();
+        assert_eq!(77, cal_num);
+    }
+
+    #[test]
+    fn _should_parse_number_string() {
+        let one = "one";
+        assert_eq!(1, parse_number_string(one));
+
+        let two = "two";
+        assert_eq!(2, parse_number_string(two));
+
+        let three = "three";
+        assert_eq!(3, parse_number_string(three));
+
+        let four = "four";
+        assert_eq!(4, parse_number_string(four));
+
+        let five = "five";
+        assert_eq!(5, parse_number_string(five));
+
+        let six = "six";
+        assert_eq!(6, parse_number_string(six));
+
+        let seven = "seven";
+        assert_eq!(7, parse_number_string(seven));
+
+        let eight = "eight";
+        assert_eq!(8, parse_number_string(eight));
+
+        let nine = "nine";
+        assert_eq!(9, parse_number_string(nine));
+    }
+
+    #[test]
+    fn _should_report_lines_without_digits() {
+        let document = Document::parse("1abc2\nnothing\ntwo1nine\n", Mode::Strict).unwrap();
+
+        let calibration = document.part1();
+        assert_eq!(12 + 11, calibration.sum);
+        assert_eq!(
+            vec![(2, Reason::NoDigits)],
+            calibration
+                .failures
+                .iter()
+                .map(|failure| (failure.line, failure.reason))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(None, get_first_digit_string("nothing"));
+        assert_eq!(
+            vec!["Part 1: line 2: no digits in `nothing`"],
+            document.notes()
+        );
+
+        let totals = stream("1abc2\r\nnothing\ntwo1nine\n".as_bytes()).unwrap();
+        assert_eq!(calibration, totals.part1);
+        assert_eq!(12 + 29, totals.part2.sum);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn _should_calibrate_real_input_in_parallel_like_sequentially() {
+        let document = Document::parse(include_str!("../data/day1"), Mode::Strict).unwrap();
+
+        for value in [get_calibration_number, get_stringified_calibration_number] {
+            assert_eq!(document.calibrate(value), document.par_calibrate(value));
+        }
+        assert_eq!(54530, document.part2().sum);
+    }
+
+    #[test]
+    fn _should_find_overlapping_digits() {
+        let digits: Vec<_> = find_digits("xtwone3eightwo")
+            .map(|digit| (digit.value, digit.offset))
+            .collect();
+        assert_eq!(vec![(2, 1), (1, 3), (3, 6), (8, 7), (2, 11)], digits);
+
+        assert_eq!(Some("two"), get_last_digit_string("xtwone3eightwo"));
+        assert_eq!(Some("1"), get_last_digit_string("pqr1stu"));
+    }
+
+    #[test]
+    fn _should_get_stringified_calibration_number() {
+        let ex0 = "two1nine";
+        assert_eq!(Ok(29), get_stringified_calibration_number(ex0));
+
+        let ex1 = "eightwothree";
+        assert_eq!(Ok(83), get_stringified_calibration_number(ex1));
+
+        let ex2 = "abcone2threexyz";
+        assert_eq!(Ok(13), get_stringified_calibration_number(ex2));
+
+        let ex3 = "xtwone3four";
+        assert_eq!(Ok(24), get_stringified_calibration_number(ex3));
+
+        let ex4 = "4nineeightseven2";
+        assert_eq!(Ok(42), get_stringified_calibration_number(ex4));
+
+        let ex5 = "zoneight234";
+        assert_eq!(Ok(14), get_stringified_calibration_number(ex5));
+
+        let ex6 = "7pqrstsixteen";
+        assert_eq!(Ok(76), get_stringified_calibration_number(ex6));
+    }
+}
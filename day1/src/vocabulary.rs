@@ -0,0 +1,269 @@
+use std::{error::Error, fs, iter, path::Path, str::FromStr, sync::OnceLock};
+
+use aho_corasick::{AhoCorasick, Input, MatchKind};
+use aoc_common::ParseError;
+
+use crate::{CalibrationError, Reason};
+
+pub const ENGLISH: [(&str, usize); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+pub const ROMAN: [(&str, usize); 10] = [
+    ("I", 1),
+    ("II", 2),
+    ("III", 3),
+    ("IV", 4),
+    ("V", 5),
+    ("VI", 6),
+    ("VII", 7),
+    ("VIII", 8),
+    ("IX", 9),
+    ("X", 10),
+];
+
+/// A numeric or spelled-out digit found at byte `offset` of a line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Digit {
+    pub value: usize,
+    pub offset: usize,
+    pub len: usize,
+}
+
+/// Words that stand for digits, recognised alongside the numerals `0` to `9`.
+///
+/// Built in code with [`Vocabulary::with_words`], or read from a file of `word = value` lines.
+#[derive(Clone, Debug, Default)]
+pub struct Vocabulary {
+    words: Vec<(String, usize)>,
+    ignore_case: bool,
+    scanner: OnceLock<AhoCorasick>,
+}
+
+impl Vocabulary {
+    /// A vocabulary of just the numerals.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The numerals and `one` to `nine`, as used by the puzzle.
+    pub fn english() -> Self {
+        Self::new().with_words(ENGLISH)
+    }
+
+    /// Adds `word`, replacing its value if it is already known.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `word` is empty, as it would match everywhere.
+    pub fn with_word(mut self, word: impl Into<String>, value: usize) -> Self {
+        let word = word.into();
+        assert!(!word.is_empty(), "a digit word should not be empty");
+
+        match self.words.iter_mut().find(|(known, _)| *known == word) {
+            Some(known) => known.1 = value,
+            None => self.words.push((word, value)),
+        }
+        self.scanner = OnceLock::new();
+        self
+    }
+
+    pub fn with_words<W: Into<String>>(self, words: impl IntoIterator<Item = (W, usize)>) -> Self {
+        words.into_iter().fold(self, |vocabulary, (word, value)| {
+            vocabulary.with_word(word, value)
+        })
+    }
+
+    /// Matches words regardless of ASCII case, so `ROMAN` also finds `iv`.
+    pub fn ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self.scanner = OnceLock::new();
+        self
+    }
+
+    /// Reads a vocabulary file, see [`Vocabulary::from_str`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Ok(fs::read_to_string(path)?.parse()?)
+    }
+
+    pub fn words(&self) -> &[(String, usize)] {
+        &self.words
+    }
+
+    /// The value of a numeral or known word.
+    pub fn value(&self, word: &str) -> Option<usize> {
+        if let [digit @ b'0'..=b'9'] = word.as_bytes() {
+            return Some(usize::from(digit - b'0'));
+        }
+        self.words
+            .iter()
+            .find(|(known, _)| {
+                if self.ignore_case {
+                    known.eq_ignore_ascii_case(word)
+                } else {
+                    known == word
+                }
+            })
+            .map(|&(_, value)| value)
+    }
+
+    /// The numerals, then the words, so a pattern's index leads back to its value.
+    fn scanner(&self) -> &AhoCorasick {
+        self.scanner.get_or_init(|| {
+            let numerals = (b'0'..=b'9').map(|digit| vec![digit]);
+            let words = self.words.iter().map(|(word, _)| word.as_bytes().to_vec());
+
+            AhoCorasick::builder()
+                .match_kind(MatchKind::LeftmostLongest)
+                .ascii_case_insensitive(self.ignore_case)
+                .build(numerals.chain(words))
+                .unwrap()
+        })
+    }
+
+    /// Every digit of `line`, in order, including overlapping words such as both digits of
+    /// `eightwo`.
+    ///
+    /// Only the longest word starting at each offset is counted, and a word lying wholly inside
+    /// an earlier match is skipped, so `VIII` reads as 8 rather than 5, 6, 1, 7, 1, 8, 1. Words
+    /// that run past the end of a match, like the `two` of `eightwo`, are still found.
+    pub fn find_digits<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Digit> + 'a {
+        let mut start = 0;
+        let mut end = 0;
+
+        iter::from_fn(move || loop {
+            let m = self
+                .scanner()
+                .find(Input::new(line).span(start.min(line.len())..line.len()))?;
+            start = m.start() + 1;
+            if m.end() <= end {
+                continue;
+            }
+            end = m.end();
+
+            let index = m.pattern().as_usize();
+            return Some(Digit {
+                value: index
+                    .checked_sub(10)
+                    .map_or(index, |word| self.words[word].1),
+                offset: m.start(),
+                len: m.len(),
+            });
+        })
+    }
+
+    /// Writes the first digit of `line` followed by the last.
+    pub fn calibration_number(&self, line: &str) -> Result<usize, CalibrationError> {
+        let mut digits = self.find_digits(line);
+        let Some(first) = digits.next() else {
+            return Err(CalibrationError::new(line, Reason::NoDigits));
+        };
+        let last = digits.last().unwrap_or(first);
+
+        let shift = last.value.checked_ilog10().unwrap_or(0) + 1;
+        10usize
+            .checked_pow(shift)
+            .and_then(|scale| first.value.checked_mul(scale))
+            .and_then(|value| value.checked_add(last.value))
+            .ok_or_else(|| CalibrationError::new(line, Reason::Overflow))
+    }
+}
+
+impl FromStr for Vocabulary {
+    type Err = ParseError;
+
+    /// Parses one `word = value` pair per line, skipping blank lines and `#` comments.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut vocabulary = Vocabulary::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let error = |token: &str, expected: &str| {
+                ParseError::new(line, token, expected).with_line(index + 1)
+            };
+
+            let content = line.trim();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+
+            let (word, value) = content
+                .split_once('=')
+                .ok_or_else(|| error(&line[line.len()..], "`= value`"))?;
+            let (word, value) = (word.trim(), value.trim());
+            if word.is_empty() {
+                return Err(error(word, "a word"));
+            }
+            let value = value.parse().map_err(|_| error(value, "a number"))?;
+
+            vocabulary = vocabulary.with_word(word, value);
+        }
+
+        Ok(vocabulary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn _should_load_vocabulary() {
+        let vocabulary: Vocabulary = "# French\nzéro = 0\nun = 1\n\ndix = 10\n".parse().unwrap();
+
+        assert_eq!(Some(0), vocabulary.value("zéro"));
+        assert_eq!(Ok(100), vocabulary.calibration_number("dixzéro"));
+        assert_eq!(Ok(11), vocabulary.calibration_number("un"));
+        assert_eq!(
+            Err(Reason::NoDigits),
+            vocabulary
+                .calibration_number("deux")
+                .map_err(|error| error.reason)
+        );
+
+        let error = "un = 1\ndeux 2".parse::<Vocabulary>().unwrap_err();
+        assert_eq!(2, error.line());
+        assert_eq!("`= value`", error.expected());
+    }
+
+    #[test]
+    fn _should_match_longest_roman_numerals_ignoring_case() {
+        let vocabulary = Vocabulary::new().with_words(ROMAN).ignore_case();
+
+        let digits: Vec<_> = vocabulary
+            .find_digits("viii and iv")
+            .map(|digit| digit.value)
+            .collect();
+        assert_eq!(vec![8, 4], digits);
+        assert_eq!(Ok(84), vocabulary.calibration_number("viii and iv"));
+    }
+
+    #[test]
+    fn _should_skip_words_nested_in_earlier_matches() {
+        let vocabulary = Vocabulary::new().with_words(ROMAN);
+        let values = |line| -> Vec<_> {
+            vocabulary
+                .find_digits(line)
+                .map(|digit| (digit.value, digit.offset))
+                .collect()
+        };
+
+        assert_eq!(vec![(7, 0)], values("VII"));
+        assert_eq!(vec![(10, 0), (4, 1)], values("XIV"));
+        assert_eq!(vec![(2, 0), (4, 1)], values("IIV"));
+
+        let english = Vocabulary::english();
+        let digits: Vec<_> = english
+            .find_digits("eightwone")
+            .map(|digit| digit.value)
+            .collect();
+        assert_eq!(vec![8, 2, 1], digits);
+    }
+}
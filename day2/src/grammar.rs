@@ -0,0 +1,192 @@
+//! The game line grammar, with whitespace allowed around every token:
+//!
+//! ```text
+//! game   = "Game" id ":" [ round { ";" round } ]
+//! round  = draw { "," draw }
+//! draw   = count colour
+//! id     = digit { digit }
+//! count  = digit { digit }
+//! colour = letter { letter }
+//! ```
+//!
+//! A colour may appear only once per round.
+
+use aoc_common::ParseError;
+
+use crate::{Draw, Game, Palette, Round};
+
+/// The separator right after `part`, a subslice of `line`, or the end of the line.
+fn after<'a>(line: &'a str, part: &str) -> &'a str {
+    let end = part.as_ptr() as usize - line.as_ptr() as usize + part.len();
+    let next = line[end..].chars().next().map_or(0, char::len_utf8);
+    &line[end..end + next]
+}
+
+fn is_number(token: &str) -> bool {
+    !token.is_empty() && token.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+pub(crate) fn parse_game(line: &str, palette: Option<&Palette>) -> Result<Game, ParseError> {
+    let (header, content) = line
+        .split_once(':')
+        .ok_or_else(|| ParseError::new(line, &line[line.len()..], "`:` after the game id"))?;
+
+    let mut tokens = header.split_whitespace();
+    match tokens.next() {
+        Some("Game") => {}
+        Some(token) => return Err(ParseError::new(line, token, "`Game`")),
+        None => return Err(ParseError::new(line, after(line, header), "`Game`")),
+    }
+    let id = match tokens.next() {
+        Some(token) if is_number(token) => token
+            .parse()
+            .map_err(|_| ParseError::new(line, token, "a game id that fits in a usize"))?,
+        Some(token) => return Err(ParseError::new(line, token, "a game id")),
+        None => return Err(ParseError::new(line, after(line, header), "a game id")),
+    };
+    if let Some(token) = tokens.next() {
+        return Err(ParseError::new(line, token, "`:` after the game id"));
+    }
+
+    if content.trim().is_empty() {
+        return Ok(Game::from_rounds(id, vec![]));
+    }
+
+    let rounds = content
+        .split(';')
+        .map(|round| parse_round(line, round, palette))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Game::from_rounds(id, rounds))
+}
+
+fn parse_round(line: &str, round: &str, palette: Option<&Palette>) -> Result<Round, ParseError> {
+    let mut draws: Vec<Draw> = vec![];
+
+    for draw in round.split(',') {
+        let mut tokens = draw.split_whitespace();
+        let (count, colour) = match (tokens.next(), tokens.next(), tokens.next()) {
+            (None, _, _) => {
+                return Err(ParseError::new(
+                    line,
+                    after(line, draw),
+                    "a draw like `3 blue`",
+                ))
+            }
+            (Some(count), Some(colour), None) => (count, colour),
+            _ => {
+                return Err(ParseError::new(
+                    line,
+                    draw.trim(),
+                    "a cube count followed by a colour",
+                ))
+            }
+        };
+
+        if !is_number(count) {
+            return Err(ParseError::new(line, count, "a cube count"));
+        }
+        let count = count
+            .parse()
+            .map_err(|_| ParseError::new(line, count, "a cube count that fits in a usize"))?;
+
+        if !colour.chars().all(char::is_alphabetic) {
+            return Err(ParseError::new(line, colour, "a colour"));
+        }
+        if let Some(palette) = palette.filter(|palette| !palette.contains(colour)) {
+            return Err(ParseError::new(line, colour, format!("one of {palette}")));
+        }
+        if draws.iter().any(|draw| draw.colour == colour) {
+            return Err(ParseError::new(
+                line,
+                colour,
+                "a colour not already drawn this round",
+            ));
+        }
+
+        draws.push(Draw {
+            count,
+            colour: colour.to_string(),
+        });
+    }
+
+    Ok(Round { draws })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Malformed lines, with the token each error should point at and what it expected.
+    const MALFORMED: [(&str, &str, &str); 20] = [
+        ("Game 1 3 blue", "", "`:` after the game id"),
+        (": 3 blue", ":", "`Game`"),
+        ("1: 3 blue", "1", "`Game`"),
+        ("Round 1: 3 blue", "Round", "`Game`"),
+        ("game 1: 3 blue", "game", "`Game`"),
+        ("Game: 3 blue", ":", "a game id"),
+        ("Game -1: 3 blue", "-1", "a game id"),
+        ("Game 1 2: 3 blue", "2", "`:` after the game id"),
+        (
+            "Game 99999999999999999999: 3 blue",
+            "99999999999999999999",
+            "a game id that fits in a usize",
+        ),
+        (
+            "Game 1: 3 blueberries",
+            "blueberries",
+            "one of blue, green, red",
+        ),
+        (
+            "Game 1: 3 blue, 2 blue",
+            "blue",
+            "a colour not already drawn this round",
+        ),
+        ("Game 1: -3 blue", "-3", "a cube count"),
+        ("Game 1: +3 blue", "+3", "a cube count"),
+        ("Game 1: 3.5 blue", "3.5", "a cube count"),
+        (
+            "Game 1: 3blue",
+            "3blue",
+            "a cube count followed by a colour",
+        ),
+        (
+            "Game 1: 3 blue red",
+            "3 blue red",
+            "a cube count followed by a colour",
+        ),
+        ("Game 1: 3 red!", "red!", "a colour"),
+        ("Game 1: 3 red,, 2 blue", ",", "a draw like `3 blue`"),
+        ("Game 1: 3 red; ; 2 blue", ";", "a draw like `3 blue`"),
+        ("Game 1: 3 red;", "", "a draw like `3 blue`"),
+    ];
+
+    #[test]
+    fn _should_reject_malformed_lines() {
+        for (line, found, expected) in MALFORMED {
+            let error = parse_game(line, Some(&Palette::standard()))
+                .expect_err(&format!("`{line}` should not parse"));
+
+            assert_eq!(
+                (found, expected),
+                (error.found(), error.expected()),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn _should_accept_well_formed_lines() {
+        for line in [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2:",
+            "  Game\t3 :  0 red ;1 blue,2 green  ",
+            "Game 4: 1 red; 1 red; 1 red",
+        ] {
+            assert!(
+                parse_game(line, Some(&Palette::standard())).is_ok(),
+                "{line}"
+            );
+        }
+    }
+}
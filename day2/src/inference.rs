@@ -0,0 +1,232 @@
+use std::cmp::Ordering;
+
+use crate::{Cubes, Game, Games, Palette, Round};
+
+/// The most bags [`Game::most_likely_bag`] will score before giving up, enough for the
+/// puzzle's three colours with up to 100 cubes each.
+pub const MAX_CANDIDATE_BAGS: usize = 101 * 101 * 101;
+
+/// A candidate bag, and how likely it is to have produced a game's rounds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoredBag {
+    pub bag: Cubes,
+    /// Natural log of the likelihood, `-inf` if the bag could not have produced the rounds.
+    pub log_likelihood: f64,
+}
+
+impl ScoredBag {
+    pub fn likelihood(&self) -> f64 {
+        self.log_likelihood.exp()
+    }
+}
+
+/// Natural log of the number of ways to choose `k` items out of `n`.
+fn ln_choose(n: usize, k: usize) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+    let k = k.min(n - k);
+    (0..k)
+        .map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln())
+        .sum()
+}
+
+impl Round {
+    /// Natural log of the chance of drawing exactly this handful out of `bag`, given its size.
+    ///
+    /// The handful is drawn without replacement, so the counts follow a multivariate
+    /// hypergeometric distribution.
+    pub fn log_likelihood(&self, bag: &Cubes) -> f64 {
+        let drawn = self.cubes();
+        let bag_size = bag.0.values().sum();
+        // Otherwise both sides of the difference below can be `-inf`, giving `NaN`.
+        if bag_size < self.total() || !bag.could_contain(&drawn) {
+            return f64::NEG_INFINITY;
+        }
+
+        drawn
+            .0
+            .iter()
+            .map(|(colour, &count)| ln_choose(bag.get(colour), count))
+            .sum::<f64>()
+            - ln_choose(bag_size, self.total())
+    }
+}
+
+impl Game {
+    /// Natural log of the chance of `bag` producing every round, with the cubes put back
+    /// between rounds.
+    pub fn log_likelihood(&self, bag: &Cubes) -> f64 {
+        self.rounds
+            .iter()
+            .map(|round| round.log_likelihood(bag))
+            .sum()
+    }
+
+    /// The chance of `bag` producing every round, see [`Game::log_likelihood`].
+    pub fn likelihood(&self, bag: &Cubes) -> f64 {
+        self.log_likelihood(bag).exp()
+    }
+
+    /// Scores each of `bags`, most likely first.
+    pub fn rank_bags(&self, bags: impl IntoIterator<Item = Cubes>) -> Vec<ScoredBag> {
+        let mut scored: Vec<_> = bags
+            .into_iter()
+            .map(|bag| ScoredBag {
+                log_likelihood: self.log_likelihood(&bag),
+                bag,
+            })
+            .collect();
+
+        scored.sort_by(|a, b| {
+            b.log_likelihood
+                .partial_cmp(&a.log_likelihood)
+                .unwrap_or(Ordering::Equal)
+        });
+        scored
+    }
+
+    /// How many bags [`Game::most_likely_bag`] scores for `palette` and `limit`: every bag
+    /// holding between the game's minimum and `limit` cubes of each colour. `None` if that
+    /// overflows a `usize`.
+    pub fn candidate_bags(&self, palette: &Palette, limit: usize) -> Option<usize> {
+        palette.colours().try_fold(1usize, |total, colour| {
+            let choices = limit
+                .saturating_add(1)
+                .saturating_sub(self.cubes.get(colour));
+            total.checked_mul(choices)
+        })
+    }
+
+    /// The maximum-likelihood bag of `palette`'s colours, holding at most `limit` cubes of
+    /// each, or `None` if no such bag could have produced the rounds.
+    ///
+    /// The colours share the bag's size, so they cannot be searched one at a time. Every
+    /// candidate is scored instead, which grows as `limit` to the power of the number of
+    /// colours (see [`Game::candidate_bags`]), so searches over more than
+    /// [`MAX_CANDIDATE_BAGS`] bags give up and return `None` as well.
+    ///
+    /// Ties go to the bag with the fewest cubes of the first colours.
+    pub fn most_likely_bag(&self, palette: &Palette, limit: usize) -> Option<ScoredBag> {
+        if self
+            .candidate_bags(palette, limit)
+            .is_none_or(|count| count > MAX_CANDIDATE_BAGS)
+        {
+            return None;
+        }
+
+        let colours: Vec<_> = palette.colours().collect();
+        let mut counts: Vec<_> = colours
+            .iter()
+            .map(|colour| self.cubes.get(colour))
+            .collect();
+        if counts.iter().any(|&count| count > limit) {
+            return None;
+        }
+
+        let mut best: Option<ScoredBag> = None;
+        loop {
+            let bag = colours
+                .iter()
+                .copied()
+                .zip(counts.iter().copied())
+                .collect();
+            let log_likelihood = self.log_likelihood(&bag);
+            if best
+                .as_ref()
+                .is_none_or(|best| log_likelihood > best.log_likelihood)
+            {
+                best = Some(ScoredBag {
+                    bag,
+                    log_likelihood,
+                });
+            }
+
+            // Step to the next bag, counting up from the last colour like an odometer.
+            let Some(index) = (0..counts.len()).rev().find(|&i| counts[i] < limit) else {
+                break;
+            };
+            counts[index] += 1;
+            for (colour, count) in colours.iter().zip(&mut counts).skip(index + 1) {
+                *count = self.cubes.get(colour);
+            }
+        }
+
+        best.filter(|best| best.log_likelihood.is_finite())
+    }
+}
+
+impl Games {
+    /// Natural log of the chance of `bag` producing every game.
+    pub fn log_likelihood(&self, bag: &Cubes) -> f64 {
+        self.games()
+            .iter()
+            .map(|game| game.log_likelihood(bag))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn _should_score_draws_without_replacement() {
+        let game: Game = "Game 1: 1 red, 1 blue".parse().unwrap();
+        let bag = "red=2,blue=2".parse().unwrap();
+
+        assert!((game.likelihood(&bag) - 4.0 / 6.0).abs() < 1e-9);
+        assert_eq!(0.0, game.likelihood(&"red=2".parse().unwrap()));
+    }
+
+    #[test]
+    fn _should_rank_bags_by_likelihood() {
+        let game: Game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
+            .parse()
+            .unwrap();
+
+        let ranked = game.rank_bags([
+            Cubes::puzzle_bag(),
+            "red=4,green=2,blue=6".parse().unwrap(),
+            "red=1,green=2,blue=6".parse().unwrap(),
+        ]);
+        let order: Vec<_> = ranked.iter().map(|scored| scored.bag.get("red")).collect();
+        assert_eq!(vec![12, 4, 1], order);
+        assert_eq!(0.0, ranked[2].likelihood());
+
+        let best = game.most_likely_bag(&Palette::standard(), 20).unwrap();
+        assert!(best.log_likelihood >= ranked[0].log_likelihood);
+        assert!(game.could_be_played_with(&best.bag));
+        assert!(game.most_likely_bag(&Palette::standard(), 5).is_none());
+    }
+
+    #[test]
+    fn _should_rank_impossible_bags_last() {
+        let game: Game = "Game 2: 15 red, 14 green, 15 blue".parse().unwrap();
+        let exact: Cubes = "red=15,green=14,blue=15".parse().unwrap();
+
+        assert_eq!(f64::NEG_INFINITY, game.log_likelihood(&Cubes::puzzle_bag()));
+        assert_eq!(0.0, game.log_likelihood(&exact));
+
+        let ranked = game.rank_bags([Cubes::puzzle_bag(), exact.clone()]);
+        assert_eq!(exact, ranked[0].bag);
+        assert_eq!(0.0, ranked[1].likelihood());
+    }
+
+    #[test]
+    fn _should_bound_bag_search() {
+        let game: Game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            Some(17 * 19 * 15),
+            game.candidate_bags(&Palette::standard(), 20)
+        );
+        assert!(game
+            .candidate_bags(&Palette::standard(), 100)
+            .is_some_and(|count| count <= MAX_CANDIDATE_BAGS));
+        assert!(game.most_likely_bag(&Palette::standard(), 200).is_none());
+        assert_eq!(None, game.candidate_bags(&Palette::standard(), usize::MAX));
+    }
+}
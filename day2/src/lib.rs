@@ -0,0 +1,683 @@
+//! Day 2: Cube Conundrum. Checking which games could have been played with a given bag.
+
+mod grammar;
+mod inference;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt,
+    io::BufRead,
+    str::FromStr,
+};
+
+use aoc_common::{skipped_summary, stream_lines_with, ParseError, Parsed, Part, Report};
+pub use aoc_common::{Mode, Solution};
+pub use inference::{ScoredBag, MAX_CANDIDATE_BAGS};
+
+/// A number of cubes of each colour, like a bag's contents or the most shown during a game.
+///
+/// Colours that are not listed count as zero cubes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cubes(pub BTreeMap<String, usize>);
+
+impl Cubes {
+    /// The bag from the puzzle: 12 red, 13 green and 14 blue cubes.
+    pub fn puzzle_bag() -> Self {
+        [("red", 12), ("green", 13), ("blue", 14)]
+            .into_iter()
+            .collect()
+    }
+
+    pub fn get(&self, colour: &str) -> usize {
+        self.0.get(colour).copied().unwrap_or(0)
+    }
+
+    /// Whether every cube in `other` could have come out of `self`.
+    pub fn could_contain(&self, other: &Cubes) -> bool {
+        other
+            .0
+            .iter()
+            .all(|(colour, &count)| count <= self.get(colour))
+    }
+
+    /// The number of cubes of each of `palette`'s colours multiplied together.
+    pub fn power(&self, palette: &Palette) -> usize {
+        palette.colours().map(|colour| self.get(colour)).product()
+    }
+
+    /// Adds cubes until `self` could contain `other` too.
+    pub fn include(&mut self, other: &Cubes) {
+        for (colour, &count) in &other.0 {
+            let most = self.0.entry(colour.clone()).or_default();
+            *most = (*most).max(count);
+        }
+    }
+}
+
+impl fmt::Display for Cubes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts: Vec<_> = self
+            .0
+            .iter()
+            .map(|(colour, count)| format!("{colour}={count}"))
+            .collect();
+        write!(f, "{}", counts.join(","))
+    }
+}
+
+/// Parses comma-separated `colour=count` pairs, like `red=12,green=13,blue=14`.
+impl FromStr for Cubes {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|pair| {
+                let (colour, count) = pair
+                    .split_once('=')
+                    .ok_or_else(|| ParseError::new(s, pair, "`colour=count`"))?;
+                let (colour, count) = (colour.trim(), count.trim());
+                if colour.is_empty() {
+                    return Err(ParseError::new(s, colour, "a colour"));
+                }
+                let count = count
+                    .parse()
+                    .map_err(|_| ParseError::new(s, count, "a cube count"))?;
+                Ok((colour, count))
+            })
+            .collect()
+    }
+}
+
+impl<C: Into<String>> FromIterator<(C, usize)> for Cubes {
+    fn from_iter<I: IntoIterator<Item = (C, usize)>>(iter: I) -> Self {
+        Cubes(
+            iter.into_iter()
+                .map(|(colour, count)| (colour.into(), count))
+                .collect(),
+        )
+    }
+}
+
+/// The colours a set of games is played with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Palette(BTreeSet<String>);
+
+impl Palette {
+    /// The colours from the puzzle: red, green and blue.
+    pub fn standard() -> Self {
+        ["red", "green", "blue"].into_iter().collect()
+    }
+
+    /// Every colour shown during `games`.
+    pub fn infer(games: &[Game]) -> Self {
+        games
+            .iter()
+            .flat_map(|game| game.rounds.iter().flat_map(|round| &round.draws))
+            .map(|draw| draw.colour.clone())
+            .collect()
+    }
+
+    pub fn contains(&self, colour: &str) -> bool {
+        self.0.contains(colour)
+    }
+
+    pub fn colours(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+impl<C: Into<String>> FromIterator<C> for Palette {
+    fn from_iter<I: IntoIterator<Item = C>>(iter: I) -> Self {
+        Palette(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let colours: Vec<_> = self.colours().collect();
+        write!(f, "{}", colours.join(", "))
+    }
+}
+
+/// Cubes of a single colour shown together, like `3 blue`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Draw {
+    pub count: usize,
+    pub colour: String,
+}
+
+/// One `;`-separated handful of cubes, its draws in the order they were written.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Round {
+    pub draws: Vec<Draw>,
+}
+
+impl Round {
+    /// Cubes of each colour shown in this round.
+    pub fn cubes(&self) -> Cubes {
+        let mut cubes = Cubes::default();
+        for draw in &self.draws {
+            *cubes.0.entry(draw.colour.clone()).or_default() += draw.count;
+        }
+        cubes
+    }
+
+    /// Cubes of every colour shown in this round.
+    pub fn total(&self) -> usize {
+        self.draws.iter().map(|draw| draw.count).sum()
+    }
+
+    /// Whether every cube of this round could have come out of `bag`.
+    pub fn fits_in(&self, bag: &Cubes) -> bool {
+        bag.could_contain(&self.cubes())
+    }
+}
+
+/// A game's rounds, along with the most cubes of each colour shown at once during it.
+///
+/// Only the id and rounds are read back when deserialising, the cubes are worked out again.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "GameRecord")
+)]
+pub struct Game {
+    pub id: usize,
+    pub cubes: Cubes,
+    pub rounds: Vec<Round>,
+}
+
+impl Game {
+    /// A game whose most cubes of each colour are taken from `rounds`.
+    pub fn from_rounds(id: usize, rounds: Vec<Round>) -> Self {
+        let mut cubes = Cubes::default();
+        for round in &rounds {
+            cubes.include(&round.cubes());
+        }
+
+        Game { id, cubes, rounds }
+    }
+
+    /// Parses a game, rejecting colours outside `palette` if one is given.
+    ///
+    /// Lines look like `Game 1: 3 blue, 4 red; 1 red`; junk such as `3 blueberries`, a
+    /// colour drawn twice in one round or a negative count is rejected.
+    pub fn parse(s: &str, palette: Option<&Palette>) -> Result<Self, ParseError> {
+        grammar::parse_game(s, palette)
+    }
+
+    /// Whether every cube shown during the game could have come out of `bag`.
+    pub fn could_be_played_with(&self, bag: &Cubes) -> bool {
+        bag.could_contain(&self.cubes)
+    }
+
+    /// The most cubes shown of each of `palette`'s colours multiplied together.
+    pub fn power(&self, palette: &Palette) -> usize {
+        self.cubes.power(palette)
+    }
+
+    /// The bag holding the most cubes, none more than `within`, that the game could not have
+    /// been played with, or `None` if every bag admits it.
+    pub fn largest_rejecting_bag(&self, within: &Cubes) -> Option<Cubes> {
+        if !within.could_contain(&self.cubes) {
+            return Some(within.clone());
+        }
+
+        // Leave out just enough cubes of the colour that costs the fewest to leave out.
+        self.cubes
+            .0
+            .iter()
+            .filter(|(_, &count)| count > 0)
+            .min_by_key(|(colour, &count)| within.get(colour) - (count - 1))
+            .map(|(colour, &count)| {
+                let mut bag = within.clone();
+                bag.0.insert(colour.clone(), count - 1);
+                bag
+            })
+    }
+
+    /// Index of the first round showing more cubes than `bag` holds.
+    pub fn first_round_exceeding(&self, bag: &Cubes) -> Option<usize> {
+        self.rounds.iter().position(|round| !round.fits_in(bag))
+    }
+
+    /// Cubes shown over every round of the game.
+    pub fn total_cubes(&self) -> usize {
+        self.rounds.iter().map(Round::total).sum()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct GameRecord {
+    id: usize,
+    rounds: Vec<Round>,
+}
+
+#[cfg(feature = "serde")]
+impl From<GameRecord> for Game {
+    fn from(value: GameRecord) -> Self {
+        Game::from_rounds(value.id, value.rounds)
+    }
+}
+
+impl fmt::Display for Draw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.count, self.colour)
+    }
+}
+
+impl fmt::Display for Round {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, draw) in self.draws.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{draw}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the game back in the puzzle's `Game 1: 3 blue, 4 red; 1 red` format.
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}:", self.id)?;
+        for (index, round) in self.rounds.iter().enumerate() {
+            let separator = if index > 0 { ";" } else { "" };
+            write!(f, "{separator} {round}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a game with any colours, see [`Game::parse`].
+impl FromStr for Game {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Game::parse(s, None)
+    }
+}
+
+/// Every game recorded in the puzzle input, and the colours they are played with.
+pub struct Games {
+    parsed: Parsed<Game>,
+    palette: Palette,
+}
+
+impl Games {
+    /// Parses every game, keeping to `palette` if one is declared, or inferring it otherwise.
+    pub fn parse_with(
+        input: &str,
+        mode: Mode,
+        palette: Option<Palette>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let parse = |line: &str| Game::parse(line, palette.as_ref());
+
+        let parsed = aoc_common::par_parse_lines_with(input, mode, parse)?;
+
+        let palette = palette.unwrap_or_else(|| Palette::infer(&parsed.items));
+        Ok(Games { parsed, palette })
+    }
+
+    pub fn games(&self) -> &[Game] {
+        &self.parsed.items
+    }
+
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Lines skipped while parsing in lenient mode.
+    pub fn skipped(&self) -> &[ParseError] {
+        &self.parsed.skipped
+    }
+
+    pub fn game(&self, id: usize) -> Option<&Game> {
+        self.games().iter().find(|game| game.id == id)
+    }
+
+    /// The smallest bag every game could have been played with.
+    pub fn minimal_bag(&self) -> Cubes {
+        let mut bag = Cubes::default();
+        for game in self.games() {
+            bag.include(&game.cubes);
+        }
+        bag
+    }
+
+    /// The smallest bag each game could have been played with, by game id.
+    pub fn minimal_bags(&self) -> impl Iterator<Item = (usize, &Cubes)> {
+        self.games().iter().map(|game| (game.id, &game.cubes))
+    }
+
+    /// The games that could have been played with `bag`.
+    pub fn feasible(&self, bag: &Cubes) -> Vec<&Game> {
+        self.games()
+            .iter()
+            .filter(|game| game.could_be_played_with(bag))
+            .collect()
+    }
+
+    /// Sums the ids of the games that could have been played with `bag`.
+    pub fn possible_id_sum(&self, bag: &Cubes) -> usize {
+        self.feasible(bag).iter().map(|game| game.id).sum()
+    }
+
+    pub fn power_sum(&self) -> usize {
+        self.games()
+            .iter()
+            .map(|game| game.power(&self.palette))
+            .sum()
+    }
+}
+
+/// Both answers, computed while reading the games one at a time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub possible_id_sum: usize,
+    pub power_sum: usize,
+    pub skipped: Vec<ParseError>,
+}
+
+impl Totals {
+    /// The answers to `parts`, noting any skipped lines.
+    pub fn report(&self, parts: &[Part]) -> Report {
+        Report {
+            answers: parts
+                .iter()
+                .map(|&part| match part {
+                    Part::One => (part, self.possible_id_sum.to_string()),
+                    Part::Two => (part, self.power_sum.to_string()),
+                })
+                .collect(),
+            notes: skipped_summary(&self.skipped).into_iter().collect(),
+        }
+    }
+}
+
+/// Sums the games read from `reader` against `bag` without holding them in memory.
+///
+/// The palette has to be declared up front, as the power of the first games depends on it.
+pub fn stream(
+    reader: impl BufRead,
+    mode: Mode,
+    bag: &Cubes,
+    palette: &Palette,
+) -> Result<Totals, Box<dyn Error>> {
+    let mut totals = Totals::default();
+
+    totals.skipped = stream_lines_with(
+        reader,
+        mode,
+        |line| Game::parse(line, Some(palette)),
+        |game| {
+            if game.could_be_played_with(bag) {
+                totals.possible_id_sum += game.id;
+            }
+            totals.power_sum += game.power(palette);
+        },
+    )?;
+
+    Ok(totals)
+}
+
+/// Like [`aoc_common::solve`], but checking part 1 against `bag` instead of the puzzle's, and
+/// noting the smallest bag and which games `bag` admits. If a `game` id is given, also notes
+/// that game's smallest bag and the largest bag within `bag` that rejects it.
+///
+/// Besides the puzzle's colours, the games may use any colour that `bag` lists. Part 2 only
+/// multiplies the colours the games show, so a bag colour that no game shows leaves it as is.
+pub fn solve_with_bag(
+    input: &str,
+    parts: &[Part],
+    mode: Mode,
+    bag: &Cubes,
+    game: Option<usize>,
+) -> Result<Report, Box<dyn Error>> {
+    let palette = Palette::standard()
+        .colours()
+        .chain(bag.0.keys().map(String::as_str))
+        .collect();
+    let mut games = Games::parse_with(input, mode, Some(palette))?;
+    games.palette = Palette::infer(games.games());
+
+    let feasible: Vec<_> = games
+        .feasible(bag)
+        .iter()
+        .map(|game| game.id.to_string())
+        .collect();
+
+    let mut notes = games.notes();
+    notes.push(format!("Minimal bag: {}", games.minimal_bag()));
+    notes.push(format!(
+        "Feasible with {bag}: {} of {} game(s): {}",
+        feasible.len(),
+        games.games().len(),
+        feasible.join(", ")
+    ));
+    if let Some(id) = game {
+        let game = games.game(id).ok_or_else(|| format!("no game {id}"))?;
+        notes.push(format!("Game {id} minimal bag: {}", game.cubes));
+        notes.push(match game.largest_rejecting_bag(bag) {
+            Some(rejecting) => format!("Game {id} largest rejecting bag within {bag}: {rejecting}"),
+            None => format!("Game {id} is admitted by every bag within {bag}"),
+        });
+    }
+
+    Ok(Report {
+        answers: parts
+            .iter()
+            .map(|&part| match part {
+                Part::One => (part, games.possible_id_sum(bag).to_string()),
+                Part::Two => (part, games.power_sum().to_string()),
+            })
+            .collect(),
+        notes,
+    })
+}
+
+impl Solution for Games {
+    type Answer = usize;
+
+    fn parse(input: &str, mode: Mode) -> Result<Self, Box<dyn Error>> {
+        Games::parse_with(input, mode, Some(Palette::standard()))
+    }
+
+    fn part1(&self) -> usize {
+        self.possible_id_sum(&Cubes::puzzle_bag())
+    }
+
+    fn part2(&self) -> usize {
+        self.power_sum()
+    }
+
+    fn notes(&self) -> Vec<String> {
+        self.parsed.skipped_summary().into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn _should_return_false_on_empty_bag() {
+        let bag = Cubes::default();
+        assert!(!bag.could_contain(
+            &[("red", 4), ("green", 2), ("blue", 6)]
+                .into_iter()
+                .collect()
+        ));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn _should_parse_real_input_in_parallel_like_sequentially() {
+        let input = include_str!("../data/day2");
+
+        let parallel = Games::parse(input, Mode::Strict).unwrap();
+        let sequential = aoc_common::parse_lines::<Game>(input, Mode::Strict).unwrap();
+
+        assert_eq!(sequential.items, parallel.games());
+        assert_eq!((2439, 63711), (parallel.part1(), parallel.part2()));
+    }
+
+    #[test]
+    fn _should_stream_games() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green\nGame 2: 20 red\n";
+        let totals = stream(
+            input.as_bytes(),
+            Mode::Strict,
+            &Cubes::puzzle_bag(),
+            &Palette::standard(),
+        )
+        .unwrap();
+
+        assert_eq!(1, totals.possible_id_sum);
+        assert_eq!(4 * 2 * 3, totals.power_sum);
+    }
+
+    #[test]
+    fn _should_keep_every_round() {
+        let game: Game = "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red"
+            .parse()
+            .unwrap();
+
+        assert_eq!(3, game.rounds.len());
+        assert_eq!(
+            Draw {
+                count: 6,
+                colour: "red".to_string()
+            },
+            game.rounds[1].draws[1]
+        );
+        assert_eq!(
+            (14, 3, 15),
+            (
+                game.cubes.get("red"),
+                game.cubes.get("green"),
+                game.cubes.get("blue")
+            )
+        );
+        assert_eq!(Some(2), game.first_round_exceeding(&Cubes::puzzle_bag()));
+        assert_eq!(10 + 9 + 32, game.total_cubes());
+    }
+
+    #[test]
+    fn _should_handle_any_colours() {
+        let input = "Game 1: 2 cyan, 1 red; 3 magenta\nGame 2: 1 cyan, 4 magenta, 2 red\n";
+
+        let games = Games::parse_with(input, Mode::Strict, None).unwrap();
+        assert_eq!(
+            vec!["cyan", "magenta", "red"],
+            games.palette().colours().collect::<Vec<_>>()
+        );
+        assert_eq!(6 + 8, games.power_sum());
+
+        let bag = [("cyan", 2), ("magenta", 3), ("red", 5)]
+            .into_iter()
+            .collect();
+        assert_eq!(1, games.possible_id_sum(&bag));
+
+        let errors = Games::parse_with(input, Mode::Strict, Some(Palette::standard()))
+            .err()
+            .unwrap();
+        assert!(errors.to_string().starts_with("2 line(s) failed to parse"));
+
+        let games = Games::parse_with(input, Mode::Lenient, Some(Palette::standard())).unwrap();
+        assert_eq!(2, games.skipped().len());
+        assert_eq!("cyan", games.skipped()[0].found());
+    }
+
+    #[test]
+    fn _should_answer_bag_queries() {
+        let input =
+            "Game 1: 3 blue, 4 red; 1 red, 2 green\nGame 2: 20 red, 1 blue\nGame 3: 2 green\n";
+        let games = Games::parse(input, Mode::Strict).unwrap();
+
+        assert_eq!("blue=3,green=2,red=20", games.minimal_bag().to_string());
+        assert_eq!(
+            vec![(1, "blue=3,green=2,red=4".to_string())],
+            games
+                .minimal_bags()
+                .take(1)
+                .map(|(id, bag)| (id, bag.to_string()))
+                .collect::<Vec<_>>()
+        );
+
+        let bag: Cubes = "red=12, green=13, blue=14".parse().unwrap();
+        let feasible: Vec<_> = games.feasible(&bag).iter().map(|game| game.id).collect();
+        assert_eq!(vec![1, 3], feasible);
+
+        let rejecting = games.game(1).unwrap().largest_rejecting_bag(&bag).unwrap();
+        assert_eq!("blue=14,green=13,red=3", rejecting.to_string());
+        assert!(!games.game(1).unwrap().could_be_played_with(&rejecting));
+        assert_eq!(None, Game::default().largest_rejecting_bag(&bag));
+
+        assert!("red=12,green".parse::<Cubes>().is_err());
+    }
+
+    #[test]
+    fn _should_keep_power_sum_with_extra_bag_colour() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green\nGame 2: 20 red, 1 blue, 2 green\n";
+        let standard =
+            solve_with_bag(input, &Part::ALL, Mode::Strict, &Cubes::puzzle_bag(), None).unwrap();
+        let bag = "red=12,green=13,blue=14,yellow=1".parse().unwrap();
+        let extra = solve_with_bag(input, &Part::ALL, Mode::Strict, &bag, Some(1)).unwrap();
+
+        assert_eq!((Part::Two, (24 + 40).to_string()), extra.answers[1]);
+        assert_eq!(standard.answers, extra.answers);
+        assert!(extra
+            .notes
+            .contains(&"Game 1 minimal bag: blue=3,green=2,red=4".to_string()));
+        assert!(solve_with_bag(input, &Part::ALL, Mode::Strict, &bag, Some(3)).is_err());
+    }
+
+    #[test]
+    fn _should_write_games_back_as_parsed() {
+        for line in include_str!("../data/day2").lines() {
+            let game: Game = line.parse().unwrap();
+
+            assert_eq!(line, game.to_string());
+            assert_eq!(game, game.to_string().parse().unwrap());
+        }
+
+        let spaced: Game = "Game  7 :1 red ,2  blue".parse().unwrap();
+        assert_eq!("Game 7: 1 red, 2 blue", spaced.to_string());
+        assert_eq!("Game 8:", Game::from_rounds(8, vec![]).to_string());
+    }
+
+    #[test]
+    fn _should_reject_unmatched_handful() {
+        let error = "Game 1: 3 blue, lots of red".parse::<Game>().unwrap_err();
+
+        assert_eq!("lots of red", error.found());
+    }
+
+    #[test]
+    fn _should_locate_invalid_game_id() {
+        let error = "Game x1: 3 blue".parse::<Game>().unwrap_err();
+
+        assert_eq!(6, error.column());
+        assert_eq!("x1", error.found());
+    }
+
+    #[test]
+    fn _should_report_missing_colon() {
+        let error = "Game 1 3 blue".parse::<Game>().unwrap_err();
+
+        assert_eq!(14, error.column());
+        assert_eq!("", error.found());
+    }
+}
@@ -0,0 +1,317 @@
+//! Day 3: Gear Ratios. Finding part numbers and gears in an engine schematic.
+
+use aoc_common::{Grid, ParseError};
+pub use aoc_common::{Mode, Solution};
+use regex::Regex;
+use std::{collections::HashSet, error::Error, num::NonZeroU32, str::FromStr, sync::LazyLock};
+
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[0-9]+").unwrap());
+
+/// The most part numbers a schematic can hold, so every id fits in a `NonZeroU32`.
+const MAX_PARTS: usize = u32::MAX as usize;
+
+/// A number in the schematic, spanning the characters `start..end` on `line`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PartNumber {
+    pub number: usize,
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A `*` symbol and the part numbers adjacent to it.
+#[derive(Clone, Debug)]
+pub struct Gear {
+    coord: (usize, usize),
+    ratios: HashSet<PartNumber>,
+}
+
+impl Gear {
+    /// Position of the gear as `(x, y)`.
+    pub fn coord(&self) -> (usize, usize) {
+        self.coord
+    }
+
+    pub fn ratios(&self) -> &HashSet<PartNumber> {
+        &self.ratios
+    }
+
+    /// The product of the two adjacent part numbers, if there are exactly two.
+    pub fn ratio(&self) -> Option<usize> {
+        (self.ratios.len() == 2).then(|| self.ratios.iter().map(|n| n.number).product())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Schematic {
+    cells: Grid<Option<char>>,
+    /// For each cell, one more than the index in `parts` of the number covering it, kept to
+    /// four bytes a cell.
+    part_ids: Grid<Option<NonZeroU32>>,
+    parts: Vec<PartNumber>,
+    gears: Vec<Gear>,
+}
+
+impl Schematic {
+    pub fn parts(&self) -> &[PartNumber] {
+        &self.parts
+    }
+
+    pub fn gears(&self) -> &[Gear] {
+        &self.gears
+    }
+
+    /// Whether `part` is adjacent to a symbol, diagonals included.
+    pub fn is_valid_part_number(&self, part: &PartNumber) -> bool {
+        let within_part =
+            |(x, y): (usize, usize)| y == part.line && (part.start..part.end).contains(&x);
+
+        (part.start..part.end)
+            .flat_map(|x| self.cells.neighbours8(x, part.line))
+            .filter(|&coord| !within_part(coord))
+            .any(|(x, y)| self.cells[(x, y)].is_some())
+    }
+
+    pub fn get_valid_part_numbers(&self) -> Vec<&PartNumber> {
+        self.parts
+            .iter()
+            .filter(|part| self.is_valid_part_number(part))
+            .collect()
+    }
+
+    /// The part number covering the cell at `(x, y)`, if any.
+    pub fn get_full_part_number(&self, x: usize, y: usize) -> Option<&PartNumber> {
+        self.part_ids
+            .get(x, y)
+            .copied()
+            .flatten()
+            .map(|id| &self.parts[id.get() as usize - 1])
+    }
+
+    pub fn valid_part_number_sum(&self) -> usize {
+        self.get_valid_part_numbers()
+            .iter()
+            .map(|part| part.number)
+            .sum()
+    }
+
+    pub fn gear_ratio_sum(&self) -> usize {
+        self.gears.iter().filter_map(Gear::ratio).sum()
+    }
+
+    fn populate_gear_ratios(&mut self) {
+        let updated_gears = self
+            .gears
+            .iter()
+            .map(|gear| {
+                let (x, y) = gear.coord;
+
+                let ratios = self
+                    .cells
+                    .neighbours8(x, y)
+                    .filter_map(|(x, y)| self.get_full_part_number(x, y))
+                    .cloned()
+                    .collect();
+
+                Gear {
+                    ratios,
+                    coord: (x, y),
+                }
+            })
+            .collect();
+        self.gears = updated_gears;
+    }
+}
+
+impl FromStr for Schematic {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        for (y, line) in s.lines().enumerate() {
+            // The grid is indexed by character, the regex by byte.
+            let (mut byte, mut column) = (0, 0);
+            for cap in NUMBER.find_iter(line) {
+                column += line[byte..cap.start()].chars().count();
+                byte = cap.end();
+
+                let error =
+                    |expected| ParseError::new(line, cap.as_str(), expected).with_line(y + 1);
+                if parts.len() >= MAX_PARTS {
+                    return Err(error("fewer part numbers"));
+                }
+
+                parts.push(PartNumber {
+                    number: cap
+                        .as_str()
+                        .parse()
+                        .map_err(|_| error("a part number that fits in a usize"))?,
+                    line: y,
+                    start: column,
+                    end: column + cap.len(),
+                });
+                column += cap.len();
+            }
+        }
+
+        let cells = Grid::from_rows(s.lines().map(|line| {
+            line.chars().map(|c| match c {
+                '.' => None,
+                c => Some(c),
+            })
+        }));
+        let gears = cells
+            .iter()
+            .filter(|(_, &cell)| cell == Some('*'))
+            .map(|(coord, _)| Gear {
+                coord,
+                ratios: HashSet::new(),
+            })
+            .collect();
+
+        let mut part_ids = Grid::new(cells.width(), cells.height(), None);
+        for (id, part) in parts.iter().enumerate() {
+            let id = u32::try_from(id + 1).ok().and_then(NonZeroU32::new);
+            for x in part.start..part.end {
+                if let Some(cell) = part_ids.get_mut(x, part.line) {
+                    *cell = id;
+                }
+            }
+        }
+
+        let mut schematic = Schematic {
+            cells,
+            part_ids,
+            parts,
+            gears,
+        };
+        schematic.populate_gear_ratios();
+
+        Ok(schematic)
+    }
+}
+
+impl Solution for Schematic {
+    type Answer = usize;
+
+    fn parse(input: &str, _mode: Mode) -> Result<Self, Box<dyn Error>> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(&self) -> usize {
+        self.valid_part_number_sum()
+    }
+
+    fn part2(&self) -> usize {
+        self.gear_ratio_sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = r"467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn should_get_valid_part_numbers() {
+        let schematic: Schematic = EXAMPLE_INPUT.parse().unwrap();
+        assert_eq!(
+            vec![
+                &PartNumber {
+                    number: 467,
+                    line: 0,
+                    start: 0,
+                    end: 3
+                },
+                &PartNumber {
+                    number: 35,
+                    line: 2,
+                    start: 2,
+                    end: 4
+                },
+                &PartNumber {
+                    number: 633,
+                    line: 2,
+                    start: 6,
+                    end: 9
+                },
+                &PartNumber {
+                    number: 617,
+                    line: 4,
+                    start: 0,
+                    end: 3,
+                },
+                &PartNumber {
+                    number: 592,
+                    line: 6,
+                    start: 2,
+                    end: 5,
+                },
+                &PartNumber {
+                    number: 755,
+                    line: 7,
+                    start: 6,
+                    end: 9,
+                },
+                &PartNumber {
+                    number: 664,
+                    line: 9,
+                    start: 1,
+                    end: 4,
+                },
+                &PartNumber {
+                    number: 598,
+                    line: 9,
+                    start: 5,
+                    end: 8
+                }
+            ],
+            schematic.get_valid_part_numbers()
+        );
+    }
+
+    #[test]
+    fn should_index_cells_by_character() {
+        let lone: Schematic = "§.5".parse().unwrap();
+        assert_eq!(0, lone.valid_part_number_sum());
+
+        let gear: Schematic = "é3*4".parse().unwrap();
+        assert_eq!(3 + 4, gear.valid_part_number_sum());
+        assert_eq!(12, gear.gear_ratio_sum());
+        assert_eq!(
+            Some(3),
+            gear.get_full_part_number(1, 0).map(|part| part.number)
+        );
+    }
+
+    #[test]
+    fn should_sum_large_generated_schematic() {
+        let gears = "2*3.".repeat(250);
+        let gap = ".".repeat(gears.len());
+        let input = format!("{gears}\n{gap}\n").repeat(250);
+
+        let schematic: Schematic = input.parse().unwrap();
+        assert_eq!(250 * 250 * 5, schematic.valid_part_number_sum());
+        assert_eq!(250 * 250 * 6, schematic.gear_ratio_sum());
+    }
+
+    #[test]
+    fn should_locate_oversized_part_number() {
+        let error = "..1..\n.*99999999999999999999999.."
+            .parse::<Schematic>()
+            .unwrap_err();
+
+        assert_eq!(2, error.line());
+        assert_eq!(2..25, error.span());
+    }
+}
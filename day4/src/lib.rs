@@ -0,0 +1,288 @@
+#![warn(clippy::pedantic)]
+
+//! Day 4: Scratchcards. Scoring cards and counting the copies they win.
+
+use std::{collections::VecDeque, error::Error, io::BufRead, str::FromStr};
+
+use aoc_common::{skipped_summary, stream_lines, ParseError, Parsed, Part, Report};
+pub use aoc_common::{Mode, Solution};
+
+/// A scratchcard, how many copies of it are held, and how many of its numbers win.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Card {
+    pub count: usize,
+    pub win_count: usize,
+}
+
+impl Card {
+    /// Points earned by a card with `count` winning numbers: 1, doubled for each extra match.
+    #[must_use]
+    pub fn calculate_score(count: usize) -> usize {
+        if count > 0 {
+            1 << (count - 1)
+        } else {
+            0
+        }
+    }
+}
+
+impl Default for Card {
+    fn default() -> Self {
+        Card {
+            count: 1,
+            win_count: 0,
+        }
+    }
+}
+
+fn parse_numbers<'a>(line: &'a str, numbers: &'a str) -> Result<Vec<&'a str>, ParseError> {
+    numbers
+        .split_whitespace()
+        .map(|n| {
+            n.parse::<usize>()
+                .map(|_| n)
+                .map_err(|_| ParseError::new(line, n, "a number"))
+        })
+        .collect()
+}
+
+/// Checks that `header`, the part of `line` before the `:`, reads `Card <id>`.
+///
+/// An empty header is reported at the `:` itself.
+fn check_header(line: &str, header: &str) -> Result<(), ParseError> {
+    const EXPECTED: &str = "`Card <id>:`";
+
+    let mut tokens = header.split_whitespace();
+    match (tokens.next(), tokens.next(), tokens.next()) {
+        (Some("Card"), Some(id), None) if id.parse::<usize>().is_ok() => Ok(()),
+        (Some("Card"), _, Some(token)) | (Some("Card"), Some(token), None) => {
+            Err(ParseError::new(line, token, EXPECTED))
+        }
+        (Some(token), _, _) => Err(ParseError::new(line, token, EXPECTED)),
+        (None, _, _) => Err(ParseError::new(
+            line,
+            &line[header.len()..=header.len()],
+            EXPECTED,
+        )),
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (header, numbers) = s.split_once(':').ok_or_else(|| {
+            let found = s.split_whitespace().next().unwrap_or(s);
+            ParseError::new(s, found, "`Card <id>:`")
+        })?;
+        check_header(s, header)?;
+
+        let (winning_numbers, numbers) = numbers
+            .split_once('|')
+            .ok_or_else(|| ParseError::new(s, &s[s.len()..], "`|` between the number lists"))?;
+
+        let winning_numbers = parse_numbers(s, winning_numbers)?;
+
+        let win_count = parse_numbers(s, numbers)?
+            .iter()
+            .filter(|n| winning_numbers.contains(n))
+            .count();
+
+        Ok(Card {
+            win_count,
+            ..Default::default()
+        })
+    }
+}
+
+/// Counts card copies as cards arrive, remembering only the copies already won for the cards
+/// ahead, so memory is bounded by the largest win count rather than the number of cards.
+#[derive(Clone, Debug, Default)]
+pub struct CardCounter {
+    pending: VecDeque<usize>,
+    total: usize,
+}
+
+impl CardCounter {
+    /// Adds the next card, returning how many copies of it are held.
+    pub fn push(&mut self, card: &Card) -> usize {
+        let count = card.count + self.pending.pop_front().unwrap_or(0);
+
+        if self.pending.len() < card.win_count {
+            self.pending.resize(card.win_count, 0);
+        }
+        for pending in self.pending.iter_mut().take(card.win_count) {
+            *pending += count;
+        }
+
+        self.total += count;
+        count
+    }
+
+    /// Every card held so far.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+/// The pile of scratchcards from the puzzle input.
+pub struct Scratchcards(Parsed<Card>);
+
+impl Scratchcards {
+    #[must_use]
+    pub fn cards(&self) -> &[Card] {
+        &self.0.items
+    }
+
+    /// Lines skipped while parsing in lenient mode.
+    #[must_use]
+    pub fn skipped(&self) -> &[ParseError] {
+        &self.0.skipped
+    }
+
+    #[must_use]
+    pub fn total_score(&self) -> usize {
+        self.cards()
+            .iter()
+            .map(|card| Card::calculate_score(card.win_count))
+            .sum()
+    }
+
+    /// Counts every card held once each card has won copies of the cards below it.
+    #[must_use]
+    pub fn total_card_count(&self) -> usize {
+        let mut counter = CardCounter::default();
+        for card in self.cards() {
+            counter.push(card);
+        }
+        counter.total()
+    }
+}
+
+/// Both answers, computed while reading the cards one at a time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub score: usize,
+    pub card_count: usize,
+    pub skipped: Vec<ParseError>,
+}
+
+impl Totals {
+    /// The answers to `parts`, noting any skipped lines.
+    #[must_use]
+    pub fn report(&self, parts: &[Part]) -> Report {
+        Report {
+            answers: parts
+                .iter()
+                .map(|&part| match part {
+                    Part::One => (part, self.score.to_string()),
+                    Part::Two => (part, self.card_count.to_string()),
+                })
+                .collect(),
+            notes: skipped_summary(&self.skipped).into_iter().collect(),
+        }
+    }
+}
+
+/// Scores and counts the cards read from `reader` without holding them in memory.
+///
+/// # Errors
+///
+/// Returns an error if `reader` fails, or in [`Mode::Strict`] if any line is not a card.
+pub fn stream(reader: impl BufRead, mode: Mode) -> Result<Totals, Box<dyn Error>> {
+    let mut score = 0;
+    let mut counter = CardCounter::default();
+
+    let skipped = stream_lines(reader, mode, |card: Card| {
+        score += Card::calculate_score(card.win_count);
+        counter.push(&card);
+    })?;
+
+    Ok(Totals {
+        score,
+        card_count: counter.total(),
+        skipped,
+    })
+}
+
+impl Solution for Scratchcards {
+    type Answer = usize;
+
+    fn parse(input: &str, mode: Mode) -> Result<Self, Box<dyn Error>> {
+        let parsed = aoc_common::par_parse_lines(input, mode)?;
+
+        Ok(Scratchcards(parsed))
+    }
+
+    fn part1(&self) -> usize {
+        self.total_score()
+    }
+
+    fn part2(&self) -> usize {
+        self.total_card_count()
+    }
+
+    fn notes(&self) -> Vec<String> {
+        self.0.skipped_summary().into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = include_str!("../data/ex4");
+
+    #[test]
+    fn _should_score_example_cards() {
+        let cards = Scratchcards::parse(EXAMPLE_INPUT, Mode::Strict).unwrap();
+
+        assert_eq!(13, cards.total_score());
+        assert_eq!(30, cards.total_card_count());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn _should_parse_real_input_in_parallel_like_sequentially() {
+        let input = include_str!("../data/day4");
+
+        let parallel = Scratchcards::parse(input, Mode::Strict).unwrap();
+        let sequential = aoc_common::parse_lines::<Card>(input, Mode::Strict).unwrap();
+
+        assert_eq!(sequential.items, parallel.cards());
+        assert_eq!((24733, 5_422_730), (parallel.part1(), parallel.part2()));
+    }
+
+    #[test]
+    fn _should_reject_malformed_cards() {
+        for (line, found) in [
+            ("41 48 | 83 86", "41"),
+            (": 41 48 | 83 86", ":"),
+            ("  : 41 48 | 83 86", ":"),
+            ("Game 1: 41 48 | 83 86", "Game"),
+            ("Card: 41 48 | 83 86", "Card"),
+            ("Card one: 41 48 | 83 86", "one"),
+            ("Card 1 2: 41 48 | 83 86", "2"),
+        ] {
+            let error = line
+                .parse::<Card>()
+                .expect_err(&format!("`{line}` should not parse"));
+
+            assert_eq!(
+                (found, "`Card <id>:`"),
+                (error.found(), error.expected()),
+                "{line}"
+            );
+        }
+
+        assert!("Card   1: 41 48 | 83 86".parse::<Card>().is_ok());
+    }
+
+    #[test]
+    fn _should_stream_example_cards() {
+        let totals = stream(EXAMPLE_INPUT.as_bytes(), Mode::Strict).unwrap();
+
+        assert_eq!((13, 30), (totals.score, totals.card_count));
+    }
+}
@@ -0,0 +1,1330 @@
+#![warn(clippy::pedantic)]
+
+//! Day 5: If You Give A Seed A Fertilizer. Following seeds through the almanac's maps.
+
+use std::{
+    collections::HashMap, error::Error, fmt, iter, ops::Range, str::FromStr, sync::OnceLock,
+};
+
+use aoc_common::{par_map, ParseError};
+pub use aoc_common::{Mode, Solution};
+
+const SEED: &str = "seed";
+const LOCATION: &str = "location";
+
+/// Rules keyed by the category they translate from.
+#[derive(Clone, Debug, Default)]
+pub struct Almanac {
+    seeds: Vec<usize>,
+    seed_ranges: Vec<Range<usize>>,
+    rules: HashMap<String, Rule>,
+    /// The seed-to-location rules inverted, from location back to seed, built on first use.
+    inverted: OnceLock<Vec<Rule>>,
+    /// Issues tolerated in [`Mode::Lenient`].
+    warnings: Vec<MappingIssue>,
+}
+
+impl Almanac {
+    #[must_use]
+    pub fn seeds(&self) -> &[usize] {
+        &self.seeds
+    }
+
+    /// The seeds line read as pairs of range start and length.
+    #[must_use]
+    pub fn seed_ranges(&self) -> &[Range<usize>] {
+        &self.seed_ranges
+    }
+
+    /// Mapping issues that were let through in [`Mode::Lenient`].
+    #[must_use]
+    pub fn warnings(&self) -> &[MappingIssue] {
+        &self.warnings
+    }
+
+    /// The rule translating numbers out of `category`, if there is one.
+    #[must_use]
+    pub fn rule_from(&self, category: &str) -> Option<&Rule> {
+        self.rules.get(category)
+    }
+
+    /// Follows the rules from `category` until reaching a category with no outgoing rule.
+    fn walk_from<'a>(&'a self, category: &str) -> impl Iterator<Item = &'a Rule> + 'a {
+        iter::successors(self.rules.get(category), |rule| {
+            self.rules.get(&rule.destination)
+        })
+        .take(self.rules.len())
+    }
+
+    fn seed_to_location(&self) -> impl DoubleEndedIterator<Item = &Rule> {
+        self.walk_from(SEED).collect::<Vec<_>>().into_iter()
+    }
+
+    fn location_to_seed(&self) -> &[Rule] {
+        self.inverted
+            .get_or_init(|| self.seed_to_location().rev().map(Rule::invert).collect())
+    }
+
+    /// Returns the rules to apply, in order, to translate a number from `from` to `to`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either category is unknown, if `to` comes before `from` in the
+    /// chain, or if the two categories are not connected at all.
+    pub fn get_path(&self, from: &str, to: &str) -> Result<Vec<&Rule>, TranslationError> {
+        for category in [from, to] {
+            if !self.rules.contains_key(category)
+                && !self.rules.values().any(|rule| rule.destination == category)
+            {
+                return Err(TranslationError::UnknownCategory(category.to_string()));
+            }
+        }
+
+        if from == to {
+            return Ok(vec![]);
+        }
+
+        let mut path = vec![];
+        for rule in self.walk_from(from) {
+            path.push(rule);
+            if rule.destination == to {
+                return Ok(path);
+            }
+        }
+
+        let reversed = self.walk_from(to).any(|rule| rule.destination == from);
+
+        let from = from.to_string();
+        let to = to.to_string();
+        if reversed {
+            Err(TranslationError::OutOfOrder { from, to })
+        } else {
+            Err(TranslationError::NoPath { from, to })
+        }
+    }
+
+    /// Translates `number` from the `from` category into the `to` category.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no path leads from `from` to `to`, see [`Almanac::get_path`].
+    pub fn translate(
+        &self,
+        from: &str,
+        to: &str,
+        number: usize,
+    ) -> Result<usize, TranslationError> {
+        Ok(self
+            .get_path(from, to)?
+            .iter()
+            .fold(number, |result, rule| rule.apply_to_number(result)))
+    }
+
+    #[must_use]
+    pub fn get_location(&self, seed_number: usize) -> usize {
+        let mut result = seed_number;
+
+        for rule in self.seed_to_location() {
+            result = rule.apply_to_number(result);
+        }
+
+        result
+    }
+
+    #[must_use]
+    pub fn get_location_ranges(&self, seed_range: Range<usize>) -> Vec<Range<usize>> {
+        let mut result = vec![seed_range];
+
+        for rule in self.seed_to_location() {
+            result = rule.apply_to_ranges(&result);
+        }
+
+        result
+    }
+
+    /// Returns every seed number that ends up at `location`, in ascending order.
+    ///
+    /// The rules are inverted on the first call and reused afterwards, so searching upward
+    /// through many locations stays cheap.
+    #[must_use]
+    pub fn get_seed_for_location(&self, location: usize) -> Vec<usize> {
+        let mut result = vec![location];
+
+        for inverted in self.location_to_seed() {
+            result = result
+                .iter()
+                .flat_map(|&number| inverted.apply_all(number))
+                .collect();
+        }
+
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+
+    /// Folds every rule of the almanac into a single seed-to-location rule.
+    #[must_use]
+    pub fn compose_rules(&self) -> Rule {
+        let mut rules = self.seed_to_location();
+        let first = rules.next().cloned().unwrap_or_default();
+
+        rules.fold(first, |composed, rule| composed.compose(rule))
+    }
+
+    #[must_use]
+    pub fn get_closest_seed_location(&self) -> usize {
+        self.seeds
+            .iter()
+            .map(|&seed_number| self.get_location(seed_number))
+            .min()
+            .unwrap_or(0)
+    }
+
+    #[must_use]
+    pub fn get_closest_location_of_seed_ranges(&self) -> usize {
+        self.seed_ranges
+            .iter()
+            .flat_map(|seed_range| self.get_location_ranges(seed_range.clone()))
+            .map(|location_range| location_range.start)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Like [`Almanac::get_closest_location_of_seed_ranges`], but translating each seed range
+    /// with [`par_map`].
+    #[must_use]
+    pub fn par_get_closest_location_of_seed_ranges(&self) -> usize {
+        par_map(&self.seed_ranges, |seed_range| {
+            self.get_location_ranges(seed_range.clone())
+                .iter()
+                .map(|location_range| location_range.start)
+                .min()
+        })
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranslationError {
+    UnknownCategory(String),
+    OutOfOrder { from: String, to: String },
+    NoPath { from: String, to: String },
+}
+
+impl fmt::Display for TranslationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslationError::UnknownCategory(category) => {
+                write!(f, "Unknown category: {category}")
+            }
+            TranslationError::OutOfOrder { from, to } => {
+                write!(f, "Cannot translate backwards from {from} to {to}")
+            }
+            TranslationError::NoPath { from, to } => {
+                write!(f, "No rules lead from {from} to {to}")
+            }
+        }
+    }
+}
+
+impl Error for TranslationError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseAlmanacError {
+    Syntax(ParseError),
+    /// A second map from `category`, with the title lines of both maps.
+    DuplicateCategory {
+        category: String,
+        first: usize,
+        second: usize,
+    },
+    /// The maps from seed stop at `reached` instead of reaching location.
+    MissingPath {
+        reached: String,
+    },
+    InvalidRule(Vec<MappingIssue>),
+}
+
+impl fmt::Display for ParseAlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unable to parse Almanac: ")?;
+        match self {
+            ParseAlmanacError::Syntax(error) => write!(f, "{error}"),
+            ParseAlmanacError::DuplicateCategory {
+                category,
+                first,
+                second,
+            } => write!(
+                f,
+                "line {second}: another map from {category}, after the one on line {first}"
+            ),
+            ParseAlmanacError::MissingPath { reached } => {
+                write!(
+                    f,
+                    "no maps lead from seed to location, they stop at {reached}"
+                )
+            }
+            ParseAlmanacError::InvalidRule(issues) => {
+                write!(f, "invalid rule")?;
+                for issue in issues {
+                    write!(f, "; {issue}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ParseAlmanacError {}
+
+impl From<ParseError> for ParseAlmanacError {
+    fn from(value: ParseError) -> Self {
+        Self::Syntax(value)
+    }
+}
+
+impl Almanac {
+    /// Parses an almanac. In [`Mode::Strict`] every [`MappingIssue`] is an error, while
+    /// [`Mode::Lenient`] keeps the recoverable ones as [`Almanac::warnings`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseAlmanacError`] if the almanac is malformed, a rule has an issue
+    /// `mode` does not tolerate, or there is no path from seed to location.
+    pub fn parse_with(s: &str, mode: Mode) -> Result<Self, ParseAlmanacError> {
+        let mut almanac = Almanac::default();
+
+        let mut sections = s.split("\n\n");
+
+        let seeds_line = sections.next().unwrap_or_default();
+        let mut line = 1 + seeds_line.matches('\n').count() + 2;
+
+        let mut seed_tokens = seeds_line.split_whitespace();
+        if seed_tokens.next() != Some("seeds:") {
+            let found = seeds_line
+                .split_whitespace()
+                .next()
+                .unwrap_or(&seeds_line[..0]);
+            return Err(ParseError::new(seeds_line, found, "`seeds:`").into());
+        }
+        let seeds = seed_tokens
+            .map(|token| {
+                token
+                    .parse::<usize>()
+                    .map_err(|_| ParseError::new(seeds_line, token, "a seed number"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let seed_ranges = seeds.clone();
+        almanac.seeds = seeds;
+
+        let seed_ranges: Vec<Range<usize>> = seed_ranges
+            .chunks_exact(2)
+            .map(|a| a[0]..a[0] + a[1])
+            .collect();
+        almanac.seed_ranges = seed_ranges;
+
+        for section in sections {
+            // Blank sections, like the one after a trailing blank line, hold no rule.
+            if section.trim().is_empty() {
+                line += section.matches('\n').count() + 2;
+                continue;
+            }
+
+            let mut rule: Rule = section.parse().map_err(|error: ParseError| {
+                let error_line = error.line() + line - 1;
+                error.with_line(error_line)
+            })?;
+            rule.line = line;
+            line += section.matches('\n').count() + 2;
+
+            if let Err(issues) = rule.validate() {
+                let (warnings, errors): (Vec<_>, Vec<_>) = issues
+                    .into_iter()
+                    .partition(|issue| mode == Mode::Lenient && issue.is_recoverable());
+                if !errors.is_empty() {
+                    return Err(ParseAlmanacError::InvalidRule(errors));
+                }
+                almanac.warnings.extend(warnings);
+            }
+            if let Some(existing) = almanac.rules.get(&rule.source) {
+                return Err(ParseAlmanacError::DuplicateCategory {
+                    category: rule.source,
+                    first: existing.line,
+                    second: rule.line,
+                });
+            }
+            almanac.rules.insert(rule.source.clone(), rule);
+        }
+
+        if almanac.get_path(SEED, LOCATION).is_err() {
+            let reached = almanac
+                .walk_from(SEED)
+                .last()
+                .map_or(SEED, |rule| rule.destination.as_str());
+            return Err(ParseAlmanacError::MissingPath {
+                reached: reached.to_string(),
+            });
+        }
+
+        Ok(almanac)
+    }
+}
+
+impl FromStr for Almanac {
+    type Err = ParseAlmanacError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Almanac::parse_with(s, Mode::Strict)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Mapping {
+    destination: usize,
+    source: usize,
+    range_length: usize,
+}
+
+impl Mapping {
+    #[must_use]
+    pub fn new(destination: usize, source: usize, range_length: usize) -> Self {
+        Mapping {
+            destination,
+            source,
+            range_length,
+        }
+    }
+
+    #[must_use]
+    pub fn destination(&self) -> usize {
+        self.destination
+    }
+
+    #[must_use]
+    pub fn source(&self) -> usize {
+        self.source
+    }
+
+    #[must_use]
+    pub fn range_length(&self) -> usize {
+        self.range_length
+    }
+
+    #[must_use]
+    pub fn source_range(&self) -> Range<usize> {
+        self.source..(self.source + self.range_length)
+    }
+
+    #[must_use]
+    pub fn apply(&self, value: usize) -> Option<usize> {
+        if self.source_range().contains(&value) {
+            return Some(value - self.source + self.destination);
+        }
+        None
+    }
+
+    #[must_use]
+    pub fn destination_range(&self) -> Range<usize> {
+        self.destination..(self.destination + self.range_length)
+    }
+
+    /// Splits `range` at the edges of this mapping's source range.
+    ///
+    /// Returns the part of this mapping restricted to the overlap with `range` (if any), and
+    /// the pieces of `range` before and after the overlap, which are left untouched. An empty
+    /// `range` has no pieces at all.
+    #[must_use]
+    pub fn split_range(&self, range: Range<usize>) -> (Option<Mapping>, Vec<Range<usize>>) {
+        if range.is_empty() {
+            return (None, vec![]);
+        }
+        let source_range = self.source_range();
+
+        let start = range.start.max(source_range.start);
+        let end = range.end.min(source_range.end);
+
+        if start >= end {
+            return (None, vec![range]);
+        }
+
+        let restricted = Mapping {
+            destination: start - self.source + self.destination,
+            source: start,
+            range_length: end - start,
+        };
+
+        let unmapped = [range.start..start, end..range.end]
+            .into_iter()
+            .filter(|piece| !piece.is_empty())
+            .collect();
+
+        (Some(restricted), unmapped)
+    }
+
+    #[must_use]
+    pub fn apply_to_range(&self, range: Range<usize>) -> (Option<Range<usize>>, Vec<Range<usize>>) {
+        let (restricted, unmapped) = self.split_range(range);
+        (
+            restricted.map(|mapping| mapping.destination_range()),
+            unmapped,
+        )
+    }
+
+    /// Swaps source and destination, so the mapping answers the reverse question.
+    #[must_use]
+    pub fn invert(&self) -> Mapping {
+        Mapping {
+            destination: self.source,
+            source: self.destination,
+            range_length: self.range_length,
+        }
+    }
+
+    fn identity(range: Range<usize>) -> Self {
+        Mapping {
+            destination: range.start,
+            source: range.start,
+            range_length: range.len(),
+        }
+    }
+
+    fn is_identity(&self) -> bool {
+        self.destination == self.source
+    }
+}
+
+impl FromStr for Mapping {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let end_of_line = &s[s.len()..];
+        let mut split = s.split_whitespace();
+
+        let mut next_number = |expected: &str| {
+            let token = split
+                .next()
+                .ok_or_else(|| ParseError::new(s, end_of_line, expected))?;
+            token
+                .parse::<usize>()
+                .map_err(|_| ParseError::new(s, token, expected))
+        };
+
+        let destination = next_number("a destination range start")?;
+        let source = next_number("a source range start")?;
+        let range_length = next_number("a range length")?;
+
+        if let Some(token) = split.next() {
+            return Err(ParseError::new(s, token, "end of line"));
+        }
+
+        Ok(Mapping {
+            destination,
+            source,
+            range_length,
+        })
+    }
+}
+
+/// A problem found in the mappings of a [`Rule`], located by line number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MappingIssue {
+    OverlappingSources { first: usize, second: usize },
+    OverlappingDestinations { first: usize, second: usize },
+    Overflow { line: usize },
+}
+
+impl MappingIssue {
+    /// Whether the rule still translates every number consistently despite the issue.
+    ///
+    /// Overlapping destinations only give some numbers several preimages, which
+    /// [`Rule::invert`] handles, so [`Mode::Lenient`] accepts them.
+    #[must_use]
+    pub fn is_recoverable(&self) -> bool {
+        matches!(self, MappingIssue::OverlappingDestinations { .. })
+    }
+}
+
+impl fmt::Display for MappingIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingIssue::OverlappingSources { first, second } => {
+                write!(f, "line {second}: source range overlaps line {first}")
+            }
+            MappingIssue::OverlappingDestinations { first, second } => {
+                write!(f, "line {second}: destination range overlaps line {first}")
+            }
+            MappingIssue::Overflow { line } => {
+                write!(f, "line {line}: range end does not fit in a usize")
+            }
+        }
+    }
+}
+
+/// Finds every pair of overlapping ranges, given as `(line, start, end)`, each as
+/// `(earlier line, later line)` where the earlier range starts first.
+fn find_overlaps(mut ranges: Vec<(usize, usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.sort_unstable_by_key(|&(line, start, _)| (start, line));
+
+    let mut overlaps = vec![];
+    // The ranges seen so far that reach past the current start, as `(line, end)`.
+    let mut open: Vec<(usize, usize)> = vec![];
+
+    for (line, start, end) in ranges {
+        if start == end {
+            continue;
+        }
+        open.retain(|&(_, open_end)| open_end > start);
+        overlaps.extend(open.iter().map(|&(open_line, _)| (open_line, line)));
+        open.push((line, end));
+    }
+
+    overlaps
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Rule {
+    source: String,
+    destination: String,
+    mappings: Vec<Mapping>,
+    /// Line of the `x-to-y map:` title in the parsed input, used to locate issues.
+    line: usize,
+}
+
+impl Rule {
+    #[must_use]
+    pub fn new(source: &str, destination: &str, mappings: Vec<Mapping>) -> Self {
+        Rule {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            mappings,
+            line: 0,
+        }
+    }
+
+    /// The category this rule translates from.
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The category this rule translates into.
+    #[must_use]
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    #[must_use]
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    /// Checks that no two mappings overlap and that every range end fits in a `usize`.
+    ///
+    /// # Errors
+    ///
+    /// Returns every issue found, each pointing at the line of the offending mapping.
+    pub fn validate(&self) -> Result<(), Vec<MappingIssue>> {
+        let mut issues = vec![];
+        let mut sources = vec![];
+        let mut destinations = vec![];
+
+        for (index, mapping) in self.mappings.iter().enumerate() {
+            let line = self.line + 1 + index;
+
+            match (
+                mapping.source.checked_add(mapping.range_length),
+                mapping.destination.checked_add(mapping.range_length),
+            ) {
+                (Some(source_end), Some(destination_end)) => {
+                    sources.push((line, mapping.source, source_end));
+                    destinations.push((line, mapping.destination, destination_end));
+                }
+                _ => issues.push(MappingIssue::Overflow { line }),
+            }
+        }
+
+        issues.extend(
+            find_overlaps(sources)
+                .into_iter()
+                .map(|(first, second)| MappingIssue::OverlappingSources { first, second }),
+        );
+        issues.extend(
+            find_overlaps(destinations)
+                .into_iter()
+                .map(|(first, second)| MappingIssue::OverlappingDestinations { first, second }),
+        );
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    #[must_use]
+    pub fn apply_to_number(&self, number: usize) -> usize {
+        self.mappings
+            .iter()
+            .find_map(|m| m.apply(number))
+            .unwrap_or(number)
+    }
+
+    /// Applies every mapping whose source range contains `number`.
+    ///
+    /// Unlike [`Rule::apply_to_number`], numbers not covered by any mapping are not passed
+    /// through, which makes this the lookup to use on an inverted rule.
+    #[must_use]
+    pub fn apply_all(&self, number: usize) -> Vec<usize> {
+        self.mappings
+            .iter()
+            .filter_map(|m| m.apply(number))
+            .collect()
+    }
+
+    /// Splits `range` into the pieces handled by each mapping of this rule.
+    ///
+    /// Pieces not covered by any mapping come back as identity mappings, so the returned
+    /// mappings always cover the whole of `range`. An empty `range` gives no mappings.
+    #[must_use]
+    pub fn split_range(&self, range: Range<usize>) -> Vec<Mapping> {
+        let mut mapped = vec![];
+        let mut unmapped: Vec<_> = iter::once(range)
+            .filter(|range| !range.is_empty())
+            .collect();
+
+        for mapping in &self.mappings {
+            let mut remaining = vec![];
+
+            for piece in unmapped {
+                let (restricted, leftovers) = mapping.split_range(piece);
+                mapped.extend(restricted);
+                remaining.extend(leftovers);
+            }
+
+            unmapped = remaining;
+        }
+
+        mapped.extend(unmapped.into_iter().map(Mapping::identity));
+        mapped
+    }
+
+    #[must_use]
+    pub fn apply_to_range(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        self.split_range(range)
+            .iter()
+            .map(Mapping::destination_range)
+            .collect()
+    }
+
+    #[must_use]
+    pub fn apply_to_ranges(&self, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+        ranges
+            .iter()
+            .flat_map(|range| self.apply_to_range(range.clone()))
+            .collect()
+    }
+
+    /// Builds the reverse of this rule, mapping destinations back to their sources.
+    ///
+    /// Pass-through gaps are included as explicit mappings. Several source ranges can land on
+    /// the same destination, so the inverted mappings may overlap and should be queried with
+    /// [`Rule::apply_all`].
+    #[must_use]
+    pub fn invert(&self) -> Rule {
+        let mut inverted: Vec<_> = self
+            .split_range(0..usize::MAX)
+            .iter()
+            .map(Mapping::invert)
+            .collect();
+        inverted.sort_by_key(|mapping| mapping.source);
+
+        Self {
+            source: self.destination.clone(),
+            destination: self.source.clone(),
+            mappings: inverted,
+            line: 0,
+        }
+    }
+
+    /// Builds the rule equivalent to applying `self` and then `next`.
+    ///
+    /// The resulting mappings are sorted by source, do not overlap, and leave out any piece
+    /// that ends up mapping a number onto itself.
+    #[must_use]
+    pub fn compose(&self, next: &Rule) -> Rule {
+        let mut segments: Vec<Mapping> = self
+            .split_range(0..usize::MAX)
+            .into_iter()
+            .flat_map(|first| {
+                next.split_range(first.destination_range())
+                    .into_iter()
+                    .map(move |second| Mapping {
+                        destination: second.destination,
+                        source: second.source - first.destination + first.source,
+                        range_length: second.range_length,
+                    })
+            })
+            .filter(|mapping| !mapping.is_identity())
+            .collect();
+        segments.sort_by_key(|mapping| mapping.source);
+
+        let mut merged: Vec<Mapping> = Vec::with_capacity(segments.len());
+        for segment in segments {
+            match merged.last_mut() {
+                Some(last)
+                    if last.source_range().end == segment.source
+                        && last.destination_range().end == segment.destination =>
+                {
+                    last.range_length += segment.range_length;
+                }
+                _ => merged.push(segment),
+            }
+        }
+
+        Self {
+            source: self.source.clone(),
+            destination: next.destination.clone(),
+            mappings: merged,
+            line: 0,
+        }
+    }
+}
+
+/// Rules are equal when they translate the same way, whichever line they were read from.
+impl PartialEq for Rule {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+            && self.destination == other.destination
+            && self.mappings == other.mappings
+    }
+}
+
+impl Eq for Rule {}
+
+impl From<Vec<Mapping>> for Rule {
+    fn from(value: Vec<Mapping>) -> Self {
+        Self {
+            mappings: value,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mappings = vec![];
+        let mut lines = s.lines();
+
+        let title = lines.next().unwrap_or_default();
+        let (source, destination) = title
+            .trim()
+            .strip_suffix(" map:")
+            .and_then(|categories| categories.split_once("-to-"))
+            .ok_or_else(|| {
+                ParseError::new(title, title.trim(), "a title like `seed-to-soil map:`")
+            })?;
+
+        for (index, line) in lines.enumerate() {
+            mappings.push(
+                line.parse()
+                    .map_err(|error: ParseError| error.with_line(index + 2))?,
+            );
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            mappings,
+            line: 1,
+        })
+    }
+}
+
+impl Solution for Almanac {
+    type Answer = usize;
+
+    fn parse(input: &str, mode: Mode) -> Result<Self, Box<dyn Error>> {
+        Ok(Almanac::parse_with(input, mode)?)
+    }
+
+    fn part1(&self) -> usize {
+        self.get_closest_seed_location()
+    }
+
+    fn part2(&self) -> usize {
+        self.par_get_closest_location_of_seed_ranges()
+    }
+
+    fn notes(&self) -> Vec<String> {
+        self.warnings
+            .iter()
+            .map(|issue| format!("Warning: {issue}"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = include_str!("../../data/ex5");
+
+    mod almanac {
+        use super::*;
+
+        #[test]
+        fn _should_get_closest_seed_location() {
+            let almanac: Almanac = EXAMPLE_INPUT.parse().expect("Should parse example");
+
+            assert_eq!(35, almanac.get_closest_seed_location());
+        }
+
+        #[test]
+        fn _should_get_closest_location_of_seed_ranges() {
+            let almanac: Almanac = EXAMPLE_INPUT.parse().expect("Should parse example");
+
+            assert_eq!(46, almanac.get_closest_location_of_seed_ranges());
+        }
+
+        #[cfg(feature = "parallel")]
+        #[test]
+        fn _should_translate_real_seed_ranges_in_parallel_like_sequentially() {
+            let almanac: Almanac = include_str!("../../data/day5").parse().unwrap();
+
+            assert_eq!(
+                almanac.get_closest_location_of_seed_ranges(),
+                almanac.par_get_closest_location_of_seed_ranges()
+            );
+            assert_eq!(60_568_880, almanac.part2());
+        }
+
+        #[test]
+        fn _should_compose_rules_into_seed_to_location() {
+            let almanac: Almanac = EXAMPLE_INPUT.parse().expect("Should parse example");
+            let composed = almanac.compose_rules();
+
+            for seed_number in 0..=100 {
+                assert_eq!(
+                    almanac.get_location(seed_number),
+                    composed.apply_to_number(seed_number)
+                );
+            }
+            assert!(composed
+                .mappings
+                .windows(2)
+                .all(|pair| pair[0].source_range().end <= pair[1].source));
+        }
+
+        #[test]
+        fn _should_locate_bad_mapping_in_almanac() {
+            let input =
+                "seeds: 1\n\nseed-to-soil map:\n50 98 2\n52 5x 48\n\nsoil-to-location map:\n0 10 5";
+
+            let Err(ParseAlmanacError::Syntax(error)) = input.parse::<Almanac>() else {
+                panic!("Should fail with a syntax error");
+            };
+            assert_eq!(5, error.line());
+            assert_eq!(3..5, error.span());
+            assert_eq!("a source range start", error.expected());
+        }
+
+        #[test]
+        fn _should_translate_between_categories() {
+            let almanac: Almanac = EXAMPLE_INPUT.parse().expect("Should parse example");
+
+            assert_eq!(Ok(81), almanac.translate("seed", "soil", 79));
+            assert_eq!(Ok(78), almanac.translate("soil", "humidity", 81));
+            assert_eq!(Ok(74), almanac.translate("fertilizer", "light", 81));
+            assert_eq!(
+                Err(TranslationError::OutOfOrder {
+                    from: "humidity".to_string(),
+                    to: "soil".to_string()
+                }),
+                almanac.translate("humidity", "soil", 78)
+            );
+            assert_eq!(
+                Err(TranslationError::UnknownCategory("sun".to_string())),
+                almanac.translate("seed", "sun", 79)
+            );
+        }
+
+        #[test]
+        fn _should_parse_sections_in_any_order() {
+            let mut sections: Vec<_> = EXAMPLE_INPUT.split("\n\n").map(str::trim).collect();
+            sections[1..].reverse();
+            let almanac: Almanac = sections
+                .join("\n\n")
+                .parse()
+                .expect("Should parse shuffled example");
+
+            assert_eq!(35, almanac.get_closest_seed_location());
+        }
+
+        #[test]
+        fn _should_report_overlapping_mappings_with_line_numbers() {
+            let input = "seeds: 1\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\nsoil-to-location map:\n0 10 5\n100 12 2\n0 30 1";
+            let error = input
+                .parse::<Almanac>()
+                .expect_err("Should reject overlaps");
+
+            assert_eq!(
+                ParseAlmanacError::InvalidRule(vec![
+                    MappingIssue::OverlappingSources {
+                        first: 8,
+                        second: 9
+                    },
+                    MappingIssue::OverlappingDestinations {
+                        first: 8,
+                        second: 10
+                    },
+                ]),
+                error
+            );
+        }
+
+        #[test]
+        fn _should_name_duplicate_and_missing_categories() {
+            let input = "seeds: 1\n\nseed-to-soil map:\n0 10 5\n\nseed-to-location map:\n0 10 5";
+            let error = input
+                .parse::<Almanac>()
+                .expect_err("Should reject duplicate");
+            assert_eq!(
+                ParseAlmanacError::DuplicateCategory {
+                    category: SEED.to_string(),
+                    first: 3,
+                    second: 6
+                },
+                error
+            );
+            assert!(error
+                .to_string()
+                .ends_with("line 6: another map from seed, after the one on line 3"));
+
+            let input = "seeds: 1\n\nseed-to-soil map:\n0 10 5\n\nwater-to-location map:\n0 10 5";
+            assert_eq!(
+                ParseAlmanacError::MissingPath {
+                    reached: "soil".to_string()
+                },
+                input.parse::<Almanac>().expect_err("Should reject gap")
+            );
+        }
+
+        #[test]
+        fn _should_skip_blank_sections() {
+            let almanac: Almanac = format!("{EXAMPLE_INPUT}\n\n")
+                .parse()
+                .expect("Should parse example with a trailing blank line");
+            assert_eq!(35, almanac.get_closest_seed_location());
+
+            let input = "seeds: 1\n\n\n\nseed-to-location map:\n0 10 5\n0 11 5";
+            assert_eq!(
+                ParseAlmanacError::InvalidRule(vec![
+                    MappingIssue::OverlappingSources {
+                        first: 6,
+                        second: 7
+                    },
+                    MappingIssue::OverlappingDestinations {
+                        first: 6,
+                        second: 7
+                    },
+                ]),
+                input
+                    .parse::<Almanac>()
+                    .expect_err("Should reject overlaps")
+            );
+        }
+
+        #[test]
+        fn _should_keep_overlapping_destinations_as_warnings_when_lenient() {
+            let input = "seeds: 1\n\nseed-to-location map:\n0 10 5\n0 20 5";
+            assert!(input.parse::<Almanac>().is_err());
+
+            let almanac =
+                Almanac::parse_with(input, Mode::Lenient).expect("Should parse leniently");
+            assert_eq!(
+                &[MappingIssue::OverlappingDestinations {
+                    first: 4,
+                    second: 5
+                }],
+                almanac.warnings()
+            );
+            assert_eq!(
+                vec!["Warning: line 5: destination range overlaps line 4"],
+                almanac.notes()
+            );
+
+            let mut seeds = almanac.get_seed_for_location(3);
+            seeds.sort_unstable();
+            assert_eq!(vec![3, 13, 23], seeds);
+        }
+
+        #[test]
+        fn _should_still_reject_overlapping_sources_when_lenient() {
+            let input = "seeds: 1\n\nseed-to-location map:\n0 10 5\n20 12 5";
+
+            assert_eq!(
+                ParseAlmanacError::InvalidRule(vec![MappingIssue::OverlappingSources {
+                    first: 4,
+                    second: 5
+                }]),
+                Almanac::parse_with(input, Mode::Lenient).expect_err("Should reject overlap")
+            );
+        }
+
+        #[test]
+        fn _should_get_seed_for_location() {
+            let almanac: Almanac = EXAMPLE_INPUT.parse().expect("Should parse example");
+
+            for seed_number in 0..=100 {
+                let location = almanac.get_location(seed_number);
+                assert!(almanac
+                    .get_seed_for_location(location)
+                    .contains(&seed_number));
+            }
+        }
+
+        #[test]
+        fn _should_find_closest_seed_range_location_by_searching_upward() {
+            let almanac: Almanac = EXAMPLE_INPUT.parse().expect("Should parse example");
+
+            let closest = (0..=100)
+                .find(|&location| {
+                    almanac
+                        .get_seed_for_location(location)
+                        .iter()
+                        .any(|seed| almanac.seed_ranges.iter().any(|r| r.contains(seed)))
+                })
+                .expect("Should find a location");
+
+            assert_eq!(almanac.get_closest_location_of_seed_ranges(), closest);
+        }
+
+        #[test]
+        fn _should_ignore_empty_seed_ranges() {
+            let almanac: Almanac = EXAMPLE_INPUT
+                .replacen("seeds: 79 14", "seeds: 0 0 79 14", 1)
+                .parse()
+                .unwrap();
+
+            assert!(almanac.get_location_ranges(0..0).is_empty());
+            assert_eq!(46, almanac.get_closest_location_of_seed_ranges());
+        }
+
+        #[test]
+        fn _should_match_seed_by_seed_locations() {
+            let almanac: Almanac = EXAMPLE_INPUT.parse().expect("Should parse example");
+
+            for seed_range in &almanac.seed_ranges {
+                let mut expected: Vec<_> = seed_range
+                    .clone()
+                    .map(|seed_number| almanac.get_location(seed_number))
+                    .collect();
+                expected.sort_unstable();
+
+                let mut actual: Vec<_> = almanac
+                    .get_location_ranges(seed_range.clone())
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                actual.sort_unstable();
+
+                assert_eq!(expected, actual);
+            }
+        }
+    }
+
+    mod mapping {
+        use super::*;
+
+        #[test]
+        fn _should_parse_mapping() {
+            let mapping: Mapping = "50 98 2".parse().expect("Should be able to parse this");
+
+            assert_eq!(
+                Mapping {
+                    destination: 50,
+                    source: 98,
+                    range_length: 2
+                },
+                mapping
+            );
+        }
+
+        #[test]
+        fn _should_keep_relative_position_on_mapping_apply() {
+            let mapping = Mapping {
+                destination: 50,
+                source: 98,
+                range_length: 2,
+            };
+
+            assert_eq!(mapping.apply(98), Some(50));
+            assert_eq!(mapping.apply(99), Some(51));
+        }
+
+        #[test]
+        fn _should_locate_missing_range_length() {
+            let error = "50 98".parse::<Mapping>().unwrap_err();
+
+            assert_eq!(6, error.column());
+            assert_eq!("a range length", error.expected());
+        }
+
+        #[test]
+        fn _should_split_range_on_mapping_apply() {
+            let mapping = Mapping {
+                destination: 50,
+                source: 98,
+                range_length: 2,
+            };
+
+            let (mapped, unmapped) = mapping.apply_to_range(90..95);
+            assert_eq!(mapped, None);
+            assert_eq!(unmapped.first(), Some(&(90..95)));
+            assert_eq!(unmapped.len(), 1);
+            assert_eq!(
+                mapping.apply_to_range(95..105),
+                (Some(50..52), vec![95..98, 100..105])
+            );
+            assert_eq!(mapping.apply_to_range(98..100), (Some(50..52), vec![]));
+            assert_eq!(mapping.apply_to_range(99..99), (None, vec![]));
+        }
+    }
+
+    mod rule {
+        use super::*;
+
+        #[test]
+        fn _should_report_overflowing_mapping() {
+            let rule: Rule = format!("seed-to-soil map:\n0 {} 2\n5 0 3", usize::MAX - 1)
+                .parse()
+                .expect("Should be able to parse this");
+
+            assert_eq!(
+                Err(vec![MappingIssue::Overflow { line: 2 }]),
+                rule.validate()
+            );
+        }
+
+        #[test]
+        fn _should_parse_rule_categories() {
+            let rule: Rule = "seed-to-soil map:\n50 98 2\n52 50 48"
+                .parse()
+                .expect("Should be able to parse this");
+
+            assert_eq!("seed", rule.source);
+            assert_eq!("soil", rule.destination);
+            assert_eq!(2, rule.mappings.len());
+        }
+
+        #[test]
+        fn _should_compare_rules_regardless_of_line() {
+            let section = "seed-to-soil map:\n50 98 2\n52 50 48";
+            let almanac: Almanac = format!("seeds: 1\n\n{section}\n\nsoil-to-location map:\n0 0 1")
+                .parse()
+                .expect("Should parse almanac");
+
+            assert_eq!(
+                Some(&section.parse::<Rule>().expect("Should parse rule")),
+                almanac.rules.get(SEED)
+            );
+        }
+
+        #[test]
+        fn _should_find_every_overlapping_pair() {
+            assert_eq!(
+                vec![(1, 2), (1, 3), (2, 3)],
+                find_overlaps(vec![(1, 0, 100), (2, 10, 20), (3, 15, 30)])
+            );
+            assert_eq!(
+                vec![(1, 2)],
+                find_overlaps(vec![(3, 20, 30), (2, 5, 10), (1, 0, 6), (4, 7, 7)])
+            );
+        }
+
+        #[test]
+        fn _should_return_all_preimages_on_invert() {
+            let rule = Rule::from(vec![
+                Mapping {
+                    destination: 0,
+                    source: 10,
+                    range_length: 5,
+                },
+                Mapping {
+                    destination: 0,
+                    source: 20,
+                    range_length: 5,
+                },
+            ]);
+            let inverted = rule.invert();
+
+            let mut preimages = inverted.apply_all(3);
+            preimages.sort_unstable();
+            assert_eq!(vec![3, 13, 23], preimages);
+
+            assert!(inverted.apply_all(12).is_empty());
+            assert_eq!(vec![15], inverted.apply_all(15));
+        }
+
+        #[test]
+        fn _should_pass_unmapped_range_pieces_through() {
+            let rule = Rule::from(vec![
+                Mapping {
+                    destination: 50,
+                    source: 98,
+                    range_length: 2,
+                },
+                Mapping {
+                    destination: 52,
+                    source: 50,
+                    range_length: 48,
+                },
+            ]);
+
+            let mut ranges = rule.apply_to_range(40..100);
+            ranges.sort_by_key(|range| range.start);
+
+            assert_eq!(vec![40..50, 50..52, 52..100], ranges);
+        }
+
+        #[test]
+        fn _should_compose_rules() {
+            let first = Rule::from(vec![Mapping {
+                destination: 10,
+                source: 0,
+                range_length: 5,
+            }]);
+            let second = Rule::from(vec![Mapping {
+                destination: 100,
+                source: 12,
+                range_length: 10,
+            }]);
+
+            assert_eq!(
+                Rule::from(vec![
+                    Mapping {
+                        destination: 10,
+                        source: 0,
+                        range_length: 2,
+                    },
+                    Mapping {
+                        destination: 100,
+                        source: 2,
+                        range_length: 3,
+                    },
+                    Mapping {
+                        destination: 100,
+                        source: 12,
+                        range_length: 10,
+                    },
+                ]),
+                first.compose(&second)
+            );
+        }
+    }
+}